@@ -0,0 +1,36 @@
+-- 初始表结构（已有数据库上执行为空操作）
+CREATE TABLE IF NOT EXISTS "user" (
+    id         SERIAL PRIMARY KEY,
+    username   TEXT NOT NULL UNIQUE,
+    password   TEXT NOT NULL,
+    role       TEXT NOT NULL DEFAULT 'user',
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS articles (
+    id         SERIAL PRIMARY KEY,
+    title      TEXT NOT NULL,
+    content_md TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW(),
+    updated_at TIMESTAMP DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS tags (
+    id   SERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS article_tags (
+    article_id INTEGER REFERENCES articles(id) ON DELETE CASCADE,
+    tag_id     INTEGER REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS site_info (
+    id          SERIAL PRIMARY KEY,
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW(),
+    visit_count BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO site_info (id, created_at, visit_count)
+VALUES (1, NOW(), 0)
+ON CONFLICT (id) DO NOTHING;
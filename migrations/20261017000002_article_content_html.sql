@@ -0,0 +1,5 @@
+-- 预渲染的文章 HTML 与目录，render_version 对应 utils::markdown::RENDER_VERSION
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS content_html   TEXT    NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS content_toc    JSONB   NOT NULL DEFAULT '[]',
+    ADD COLUMN IF NOT EXISTS render_version INTEGER NOT NULL DEFAULT 0;
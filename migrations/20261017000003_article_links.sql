@@ -0,0 +1,12 @@
+-- 维基链接构成的文章引用关系，保存文章时更新
+CREATE TABLE IF NOT EXISTS article_links (
+    source_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    target_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    PRIMARY KEY (source_id, target_id)
+);
+
+CREATE INDEX IF NOT EXISTS article_links_target_idx ON article_links (target_id);
+
+-- 找不到目标的维基链接，供后台提示
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS broken_links TEXT[] NOT NULL DEFAULT '{}';
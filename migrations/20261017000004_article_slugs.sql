@@ -0,0 +1,11 @@
+-- 文章 slug，用于 /posts/<year>/<slug>；旧文章的 slug 在启动时由标题生成
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS slug TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS articles_slug_key ON articles (slug);
+
+-- 改过的旧 slug，访问时 301 到当前地址
+CREATE TABLE IF NOT EXISTS article_slug_history (
+    slug       TEXT    PRIMARY KEY,
+    article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE
+);
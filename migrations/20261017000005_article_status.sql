@@ -0,0 +1,17 @@
+-- 文章状态：draft 草稿 / published 已发布 / scheduled 定时发布 / unlisted 不公开列出
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS status       TEXT      NOT NULL DEFAULT 'published',
+    ADD COLUMN IF NOT EXISTS published_at TIMESTAMP;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'articles_status_check') THEN
+        ALTER TABLE articles ADD CONSTRAINT articles_status_check
+            CHECK (status IN ('draft', 'published', 'scheduled', 'unlisted'));
+    END IF;
+END $$;
+
+-- 已有文章视为在创建时发布
+UPDATE articles SET published_at = created_at WHERE published_at IS NULL AND status = 'published';
+
+CREATE INDEX IF NOT EXISTS articles_scheduled_idx ON articles (published_at) WHERE status = 'scheduled';
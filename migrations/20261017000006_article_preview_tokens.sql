@@ -0,0 +1,10 @@
+-- 草稿预览链接：持有 token 即可在有效期内查看未发布的文章
+CREATE TABLE IF NOT EXISTS article_preview_tokens (
+    token       TEXT      PRIMARY KEY,
+    article_id  INTEGER   NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    expires_at  TIMESTAMP NOT NULL,
+    revoked_at  TIMESTAMP,
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS article_preview_tokens_article_idx ON article_preview_tokens (article_id);
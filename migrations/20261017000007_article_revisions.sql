@@ -0,0 +1,16 @@
+-- 文章历史版本：每次保存都记录一份标题和正文
+CREATE TABLE IF NOT EXISTS article_revisions (
+    id          SERIAL    PRIMARY KEY,
+    article_id  INTEGER   NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    title       TEXT      NOT NULL,
+    content_md  TEXT      NOT NULL,
+    created_at  TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS article_revisions_article_idx ON article_revisions (article_id, id);
+
+-- 已有文章以当前内容作为第一个版本
+INSERT INTO article_revisions (article_id, title, content_md, created_at)
+SELECT a.id, a.title, a.content_md, COALESCE(a.updated_at, a.created_at, NOW())
+FROM articles a
+WHERE NOT EXISTS (SELECT 1 FROM article_revisions r WHERE r.article_id = a.id);
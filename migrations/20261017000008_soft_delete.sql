@@ -0,0 +1,6 @@
+-- 回收站：删除的文章和标签先标记 deleted_at，过了保留期再永久删除
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP;
+ALTER TABLE tags     ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS articles_deleted_idx ON articles (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS tags_deleted_idx     ON tags (deleted_at)     WHERE deleted_at IS NOT NULL;
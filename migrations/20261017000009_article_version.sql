@@ -0,0 +1,2 @@
+-- 文章版本号：每次保存加一，用于检测多个编辑页面同时保存造成的覆盖
+ALTER TABLE articles ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
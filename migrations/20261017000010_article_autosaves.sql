@@ -0,0 +1,12 @@
+-- 编辑器自动保存：每个用户每篇文章一份，article_id 为空表示新建文章
+CREATE TABLE IF NOT EXISTS article_autosaves (
+    user_id     INTEGER   NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    article_id  INTEGER   REFERENCES articles(id) ON DELETE CASCADE,
+    title       TEXT      NOT NULL,
+    content_md  TEXT      NOT NULL,
+    tag_ids     INTEGER[] NOT NULL DEFAULT '{}',
+    saved_at    TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS article_autosaves_slot_key
+    ON article_autosaves (user_id, COALESCE(article_id, 0));
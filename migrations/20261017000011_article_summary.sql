@@ -0,0 +1,4 @@
+-- 摘要：summary 为手写摘要（可空），excerpt 为渲染时自动生成的摘要
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS summary TEXT,
+    ADD COLUMN IF NOT EXISTS excerpt TEXT NOT NULL DEFAULT '';
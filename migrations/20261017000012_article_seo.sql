@@ -0,0 +1,5 @@
+-- 分享与搜索引擎用的元信息，均可空：封面图、SEO 描述、规范链接
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS cover_image TEXT,
+    ADD COLUMN IF NOT EXISTS seo_description TEXT,
+    ADD COLUMN IF NOT EXISTS canonical_url TEXT;
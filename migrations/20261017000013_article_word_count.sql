@@ -0,0 +1,4 @@
+-- 字数与预计阅读时间，保存时由渲染管线计算
+ALTER TABLE articles
+    ADD COLUMN IF NOT EXISTS word_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS reading_minutes INTEGER NOT NULL DEFAULT 0;
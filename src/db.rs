@@ -1,5 +1,10 @@
 use sqlx::{PgPool, Error, Row};
+use sqlx::types::Json;
 use crate::models::*;
+use crate::utils::{similarity, slug};
+use crate::utils::markdown::{
+    self, ArticleRef, RenderContext, RenderedMarkdown, TocItem, WikiTarget, RENDER_VERSION,
+};
 use chrono::Datelike;
 use std::collections::HashMap;
 
@@ -11,12 +16,31 @@ fn merge_articles(rows: Vec<sqlx::postgres::PgRow>) -> Vec<Article> {
 
     for row in rows {
         let id: i32 = row.get("id");
+        let slug: String = row.get::<Option<String>, _>("slug").unwrap_or_default();
+        let created_at: Option<chrono::NaiveDateTime> = row.get("created_at");
+        let published_at: Option<chrono::NaiveDateTime> = row.get("published_at");
 
         let entry = map.entry(id).or_insert(Article {
             id,
             title: row.get("title"),
+            url: article_url(id, published_at.or(created_at), &slug),
+            slug,
             content_md: row.get("content_md"),
-            created_at: row.get("created_at"),
+            content_html: row.get("content_html"),
+            toc: row.get::<Json<Vec<TocItem>>, _>("content_toc").0,
+            render_version: row.get("render_version"),
+            broken_links: row.get("broken_links"),
+            status: ArticleStatus::from_db(row.get("status")),
+            published_at,
+            version: row.get("version"),
+            summary: row.get("summary"),
+            cover_image: row.get("cover_image"),
+            seo_description: row.get("seo_description"),
+            canonical_url: row.get("canonical_url"),
+            word_count: row.get("word_count"),
+            reading_minutes: row.get("reading_minutes"),
+            excerpt: row.get("excerpt"),
+            created_at,
             updated_at: row.get("updated_at"),
             tags: Vec::new(),
         });
@@ -26,8 +50,9 @@ fn merge_articles(rows: Vec<sqlx::postgres::PgRow>) -> Vec<Article> {
         }
     }
 
+    // 与 SQL 中的 `COALESCE(published_at, created_at) DESC NULLS LAST, id DESC` 一致
     let mut out: Vec<Article> = map.into_values().collect();
-    out.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    out.sort_by_key(|a| std::cmp::Reverse((a.listed_at(), a.id)));
     out
 }
 
@@ -35,16 +60,23 @@ fn merge_articles(rows: Vec<sqlx::postgres::PgRow>) -> Vec<Article> {
 /// -----------------------------
 /// 获取全部文章
 /// -----------------------------
-pub async fn get_all_articles(pool: &PgPool) -> Result<Vec<Article>, Error> {
+/// 前台只返回已发布的文章，后台传 `include_hidden` 获取全部
+pub async fn get_all_articles(pool: &PgPool, include_hidden: bool) -> Result<Vec<Article>, Error> {
     let rows = sqlx::query(
         r#"
-        SELECT a.id,a.title,a.content_md,a.created_at,a.updated_at,
+        SELECT a.id,a.title,a.slug,a.content_md,a.content_html,a.content_toc,a.render_version,a.broken_links,
+               a.status,a.published_at,a.version,a.summary,a.cover_image,a.seo_description,a.canonical_url,
+               a.word_count,a.reading_minutes,
+               COALESCE(NULLIF(a.summary, ''), a.excerpt) AS excerpt,
+               a.created_at,a.updated_at,
                at.tag_id
         FROM articles a
         LEFT JOIN article_tags at ON a.id = at.article_id
-        ORDER BY a.created_at DESC
+        WHERE a.deleted_at IS NULL AND ($1 OR a.status = 'published')
+        ORDER BY COALESCE(a.published_at, a.created_at) DESC NULLS LAST, a.id DESC
         "#
     )
+    .bind(include_hidden)
     .fetch_all(pool)
     .await?;
 
@@ -58,7 +90,7 @@ pub async fn get_all_articles(pool: &PgPool) -> Result<Vec<Article>, Error> {
 pub async fn get_all_tags(pool: &PgPool) -> Result<Vec<Tag>, Error> {
     sqlx::query_as!(
         Tag,
-        "SELECT id,name FROM tags"
+        "SELECT id,name FROM tags WHERE deleted_at IS NULL"
     )
     .fetch_all(pool)
     .await
@@ -73,12 +105,16 @@ pub async fn get_articles_by_tag(pool: &PgPool, tag_id: i32)
 {
     let rows = sqlx::query(
         r#"
-        SELECT a.id,a.title,a.content_md,a.created_at,a.updated_at,
+        SELECT a.id,a.title,a.slug,a.content_md,a.content_html,a.content_toc,a.render_version,a.broken_links,
+               a.status,a.published_at,a.version,a.summary,a.cover_image,a.seo_description,a.canonical_url,
+               a.word_count,a.reading_minutes,
+               COALESCE(NULLIF(a.summary, ''), a.excerpt) AS excerpt,
+               a.created_at,a.updated_at,
                at.tag_id
         FROM articles a
         JOIN article_tags at ON a.id = at.article_id
-        WHERE at.tag_id=$1
-        ORDER BY a.created_at DESC
+        WHERE at.tag_id=$1 AND a.status = 'published' AND a.deleted_at IS NULL
+        ORDER BY COALESCE(a.published_at, a.created_at) DESC NULLS LAST, a.id DESC
         "#
     )
     .bind(tag_id)
@@ -97,11 +133,15 @@ pub async fn get_article_by_id(id: i32, pool: &PgPool)
 {
     let rows = sqlx::query(
         r#"
-        SELECT a.id,a.title,a.content_md,a.created_at,a.updated_at,
+        SELECT a.id,a.title,a.slug,a.content_md,a.content_html,a.content_toc,a.render_version,a.broken_links,
+               a.status,a.published_at,a.version,a.summary,a.cover_image,a.seo_description,a.canonical_url,
+               a.word_count,a.reading_minutes,
+               COALESCE(NULLIF(a.summary, ''), a.excerpt) AS excerpt,
+               a.created_at,a.updated_at,
                at.tag_id
         FROM articles a
         LEFT JOIN article_tags at ON a.id = at.article_id
-        WHERE a.id=$1
+        WHERE a.id=$1 AND a.deleted_at IS NULL
         "#
     )
     .bind(id)
@@ -122,18 +162,28 @@ pub async fn get_article_by_id(id: i32, pool: &PgPool)
 pub async fn get_recent_articles(pool: &PgPool, limit: i64)
     -> Result<Vec<RecentArticleView>, Error>
 {
-    sqlx::query_as!(
-        RecentArticleView,
+    let rows = sqlx::query!(
         r#"
-        SELECT id,title,created_at
+        SELECT id,title,slug,created_at,COALESCE(published_at, created_at) AS listed_at,reading_minutes,
+               COALESCE(NULLIF(summary, ''), excerpt) AS "excerpt!"
         FROM articles
-        ORDER BY created_at DESC
+        WHERE status = 'published' AND deleted_at IS NULL
+        ORDER BY COALESCE(published_at, created_at) DESC NULLS LAST, id DESC
         LIMIT $1
         "#,
         limit
     )
     .fetch_all(pool)
-    .await
+    .await?;
+
+    Ok(rows.into_iter().map(|row| RecentArticleView {
+        url: article_url(row.id, row.listed_at, row.slug.as_deref().unwrap_or_default()),
+        id: row.id,
+        title: row.title,
+        excerpt: row.excerpt,
+        reading_minutes: row.reading_minutes,
+        created_at: row.created_at,
+    }).collect())
 }
 
 
@@ -151,9 +201,11 @@ pub async fn get_popular_tags(
         SELECT
             t.id,
             t.name,
-            COALESCE(COUNT(at.article_id), 0) AS article_count
+            COALESCE(COUNT(a.id), 0) AS article_count
         FROM tags t
         LEFT JOIN article_tags at ON t.id = at.tag_id
+        LEFT JOIN articles a ON a.id = at.article_id AND a.status = 'published' AND a.deleted_at IS NULL
+        WHERE t.deleted_at IS NULL
         GROUP BY t.id
         ORDER BY article_count DESC
         LIMIT $1
@@ -172,14 +224,14 @@ pub async fn get_blog_stats(pool: &PgPool)
     -> Result<BlogStats, sqlx::Error>
 {
     let article_count = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM articles"
+        "SELECT COUNT(*) FROM articles WHERE status = 'published' AND deleted_at IS NULL"
     )
     .fetch_one(pool)
     .await?
     .unwrap_or(0);
 
     let tag_count = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM tags"
+        "SELECT COUNT(*) FROM tags WHERE deleted_at IS NULL"
     )
     .fetch_one(pool)
     .await?
@@ -233,7 +285,7 @@ pub async fn get_tags_by_article_id(pool: &PgPool, article_id: i32)
         SELECT t.id, t.name
         FROM tags t
         JOIN article_tags at ON t.id = at.tag_id
-        WHERE at.article_id = $1
+        WHERE at.article_id = $1 AND t.deleted_at IS NULL
         "#,
         article_id
     )
@@ -247,11 +299,17 @@ pub async fn get_articles_grouped_by_year(pool: &PgPool) -> Result<Vec<(i32, Vec
     // 获取文章及其标签
     let rows = sqlx::query!(
         r#"
-        SELECT a.id, a.title, a.content_md, a.created_at, a.updated_at,
+        SELECT a.id, a.title, a.slug, a.content_md, a.content_html,
+               a.content_toc AS "content_toc: Json<Vec<TocItem>>",
+               a.render_version, a.broken_links, a.status, a.published_at, a.version,
+               a.summary, COALESCE(NULLIF(a.summary, ''), a.excerpt) AS "excerpt!",
+               a.cover_image, a.seo_description, a.canonical_url, a.word_count, a.reading_minutes,
+               a.created_at, a.updated_at,
                at.tag_id
         FROM articles a
         LEFT JOIN article_tags at ON a.id = at.article_id
-        ORDER BY a.created_at DESC
+        WHERE a.status = 'published' AND a.deleted_at IS NULL
+        ORDER BY COALESCE(a.published_at, a.created_at) DESC NULLS LAST, a.id DESC
         "#
     )
     .fetch_all(pool)
@@ -261,10 +319,27 @@ pub async fn get_articles_grouped_by_year(pool: &PgPool) -> Result<Vec<(i32, Vec
     let mut map: HashMap<i32, Article> = HashMap::new();
 
     for row in rows {
+        let slug = row.slug.clone().unwrap_or_default();
         let entry = map.entry(row.id).or_insert(Article {
             id: row.id,
             title: row.title.clone(),
+            url: article_url(row.id, row.published_at.or(row.created_at), &slug),
+            slug,
             content_md: row.content_md.clone(),
+            content_html: row.content_html.clone(),
+            toc: row.content_toc.0.clone(),
+            render_version: row.render_version,
+            broken_links: row.broken_links.clone(),
+            status: ArticleStatus::from_db(&row.status),
+            published_at: row.published_at,
+            version: row.version,
+            summary: row.summary.clone(),
+            cover_image: row.cover_image.clone(),
+            seo_description: row.seo_description.clone(),
+            canonical_url: row.canonical_url.clone(),
+            word_count: row.word_count,
+            reading_minutes: row.reading_minutes,
+            excerpt: row.excerpt.clone(),
             created_at: row.created_at,
             updated_at: row.updated_at,
             tags: vec![],
@@ -275,28 +350,204 @@ pub async fn get_articles_grouped_by_year(pool: &PgPool) -> Result<Vec<(i32, Vec
         }
     }
 
-    // 按年份分组
+    // 按年份分组，年内顺序与列表页一致
+    let mut articles: Vec<Article> = map.into_values().collect();
+    articles.sort_by_key(|a| std::cmp::Reverse((a.listed_at(), a.id)));
     let mut articles_by_year: HashMap<i32, Vec<Article>> = HashMap::new();
 
-    for article in map.into_values() {
-        let year = article.created_at.map(|dt| dt.year()).unwrap_or(0);
+    for article in articles {
+        // 与永久链接中的年份一致
+        let year = article.listed_at().map(|dt| dt.year()).unwrap_or(0);
         articles_by_year.entry(year).or_default().push(article);
     }
     // 转换为排序后的向量
     let mut result: Vec<(i32, Vec<Article>)> = articles_by_year.into_iter().collect();
-    result.sort_by(|a, b| b.0.cmp(&a.0));
+    result.sort_by_key(|a| std::cmp::Reverse(a.0));
 
     Ok(result)
 }
 
-pub async fn create_article(pool: &PgPool, title: &str, content_md: &str, tag_ids: &[i32]) -> Result<(), sqlx::Error> {
+/// -----------------------------
+/// 渲染文章内容
+/// -----------------------------
+/// 先查询短代码和维基链接引用的文章，再交给 Markdown 管线渲染
+///
+/// 只有已发布的文章可以被引用，草稿、定时和不公开列出的文章按失效链接处理，发布后由 `rerender_referrers` 更新。
+pub async fn render_article_content(pool: &PgPool, content_md: &str)
+    -> Result<RenderedMarkdown, Error>
+{
+    let mut ids = markdown::referenced_article_ids(content_md);
+    let mut titles = Vec::new();
+    for target in markdown::referenced_wikilinks(content_md) {
+        match target {
+            WikiTarget::Id(id) => ids.push(id),
+            WikiTarget::Title(title) => titles.push(title.trim().to_lowercase()),
+        }
+    }
+
+    let mut ctx = RenderContext::default();
+    if !ids.is_empty() || !titles.is_empty() {
+        let rows = sqlx::query!(
+            "SELECT id, title, slug, COALESCE(published_at, created_at) AS listed_at FROM articles
+             WHERE status = 'published' AND deleted_at IS NULL
+               AND (id = ANY($1) OR lower(trim(title)) = ANY($2))",
+            &ids, &titles
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for row in rows {
+            ctx.articles.insert(row.id, ArticleRef {
+                id: row.id,
+                url: article_url(row.id, row.listed_at, row.slug.as_deref().unwrap_or_default()),
+                title: row.title,
+            });
+        }
+    }
+
+    Ok(markdown::render_markdown(content_md, &ctx))
+}
+
+/// 用渲染结果替换文章的引用关系
+async fn save_article_links(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: i32,
+    links: &[i32],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM article_links WHERE source_id=$1", id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query!(
+        "INSERT INTO article_links (source_id, target_id)
+         SELECT $1, target FROM UNNEST($2::INTEGER[]) AS target
+         WHERE target <> $1",
+        id, links
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// -----------------------------
+/// 重新渲染引用方
+/// -----------------------------
+/// 文章新建、改名、发布状态变化或删除后，引用它的文章（包括此前因找不到它而失效的链接）需要重新渲染
+async fn rerender_referrers(pool: &PgPool, id: i32, title: &str) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id FROM articles
+        WHERE id <> $1
+          AND (id IN (SELECT source_id FROM article_links WHERE target_id=$1)
+               OR EXISTS (SELECT 1 FROM UNNEST(broken_links) AS link
+                          WHERE lower(trim(link)) IN (lower(trim($2)), '#' || $1)))
+        "#,
+        id, title
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for row in rows {
+        rerender_article(pool, row.id).await?;
+    }
+
+    Ok(())
+}
+
+/// 重新渲染单篇文章并更新引用关系
+async fn rerender_article(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    let row = sqlx::query!("SELECT content_md FROM articles WHERE id=$1", id)
+        .fetch_one(pool)
+        .await?;
+    let rendered = render_article_content(pool, &row.content_md).await?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        "UPDATE articles SET content_html=$1, content_toc=$2, render_version=$3, broken_links=$4, excerpt=$5,
+                             word_count=$6, reading_minutes=$7
+         WHERE id=$8",
+        rendered.html, Json(&rendered.toc) as _, RENDER_VERSION, &rendered.broken_links, rendered.excerpt,
+        rendered.word_count as i32, rendered.reading_minutes as i32, id
+    )
+    .execute(&mut *tx)
+    .await?;
+    save_article_links(&mut tx, id, &rendered.links).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// -----------------------------
+/// 生成唯一 slug
+/// -----------------------------
+/// `input` 为空时由标题生成；与其他文章重复时追加 `-2`、`-3`……
+/// 被占用的 slug 若在历史记录中，则从历史中移除（当前地址优先）
+async fn unique_slug(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    title: &str,
+    input: &str,
+    exclude_id: Option<i32>,
+) -> Result<String, sqlx::Error> {
+    let base = if input.trim().is_empty() { slug::slugify(title) } else { slug::slugify(input) };
+
+    let mut candidate = base.clone();
+    let mut n = 1;
+    loop {
+        let taken = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM articles WHERE slug=$1 AND id IS DISTINCT FROM $2) AS "taken!""#,
+            candidate, exclude_id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        if !taken {
+            break;
+        }
+        n += 1;
+        candidate = format!("{}-{}", base, n);
+    }
+
+    sqlx::query!("DELETE FROM article_slug_history WHERE slug=$1", candidate)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(candidate)
+}
+
+/// 记录文章的一个历史版本
+async fn save_revision(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    article_id: i32,
+    title: &str,
+    content_md: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO article_revisions (article_id, title, content_md) VALUES ($1, $2, $3)",
+        article_id, title, content_md
+    )
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_article(pool: &PgPool, fields: &ArticleFields) -> Result<(), sqlx::Error> {
+    let ArticleFields { title, slug, summary, cover_image, seo_description, canonical_url, content_md, tag_ids, publication } = fields;
+    let rendered = render_article_content(pool, content_md).await?;
     let mut tx = pool.begin().await?;
 
+    let slug = unique_slug(&mut tx, title, slug, None).await?;
     let rec = sqlx::query!(
-        "INSERT INTO articles (title, content_md, created_at, updated_at)
-         VALUES ($1, $2, NOW(), NOW())
+        "INSERT INTO articles (title, slug, content_md, content_html, content_toc, render_version, broken_links,
+                               status, published_at, summary, excerpt, cover_image, seo_description, canonical_url,
+                               word_count, reading_minutes, created_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7,
+                 $8, CASE WHEN $8 = 'published' THEN COALESCE($9::timestamp, NOW()) ELSE $9 END,
+                 $10, $11, $12, $13, $14, $15, $16, NOW(), NOW())
          RETURNING id",
-        title, content_md
+        title, slug, content_md, rendered.html, Json(&rendered.toc) as _, RENDER_VERSION, &rendered.broken_links,
+        publication.status.as_str(), publication.published_at, summary.as_deref(), rendered.excerpt,
+        cover_image.as_deref(), seo_description.as_deref(), canonical_url.as_deref(),
+        rendered.word_count as i32, rendered.reading_minutes as i32
     )
     .fetch_one(&mut *tx)
     .await?;
@@ -309,20 +560,84 @@ pub async fn create_article(pool: &PgPool, title: &str, content_md: &str, tag_id
             .await?;
     }
 
+    save_article_links(&mut tx, article_id, &rendered.links).await?;
+    save_revision(&mut tx, article_id, title, content_md).await?;
+
     tx.commit().await?;
+
+    rerender_referrers(pool, article_id, title).await?;
     Ok(())
 }
 
-pub async fn update_article(pool: &PgPool, id: i32, title: &str, content_md: &str, tag_ids: &[i32]) -> Result<(), sqlx::Error> {
+/// 更新文章的结果
+pub enum UpdateResult {
+    Saved,
+    /// 打开编辑页之后文章已被保存过，附带当前内容
+    Conflict(Box<Article>),
+}
+
+/// 更新文章
+///
+/// `version` 为打开编辑页时文章的版本号，与当前版本不一致时不保存，返回 `Conflict`。
+pub async fn update_article(pool: &PgPool, id: i32, version: i32, fields: &ArticleFields) -> Result<UpdateResult, sqlx::Error> {
+    let ArticleFields { title, slug, summary, cover_image, seo_description, canonical_url, content_md, tag_ids, publication } = fields;
+    let rendered = render_article_content(pool, content_md).await?;
     let mut tx = pool.begin().await?;
 
-    sqlx::query!("UPDATE articles SET title=$1, content_md=$2, updated_at=NOW() WHERE id=$3", title, content_md, id)
-        .execute(&mut *tx)
+    // 锁住该行，避免两次保存同时通过版本检查
+    let old = sqlx::query!(
+        "SELECT title, slug, status, version, COALESCE(published_at, created_at) AS listed_at
+         FROM articles WHERE id=$1 FOR UPDATE",
+        id
+    )
+        .fetch_one(&mut *tx)
         .await?;
 
-    sqlx::query!("DELETE FROM article_tags WHERE article_id=$1", id)
+    if old.version != version {
+        tx.rollback().await?;
+        return Ok(UpdateResult::Conflict(Box::new(get_article_by_id(id, pool).await?)));
+    }
+
+    let slug = unique_slug(&mut tx, title, slug, Some(id)).await?;
+    let old_url = article_url(id, old.listed_at, old.slug.as_deref().unwrap_or_default());
+    if let Some(old_slug) = old.slug.as_ref().filter(|old_slug| **old_slug != slug) {
+        // 旧 slug 记入历史，之后访问时 301 到新地址
+        sqlx::query!(
+            "INSERT INTO article_slug_history (slug, article_id) VALUES ($1, $2)
+             ON CONFLICT (slug) DO UPDATE SET article_id = EXCLUDED.article_id",
+            old_slug, id
+        )
         .execute(&mut *tx)
         .await?;
+    }
+
+    let saved = sqlx::query!(
+        "UPDATE articles
+         SET title=$1, slug=$2, content_md=$3, content_html=$4, content_toc=$5, render_version=$6, broken_links=$7,
+             status=$8,
+             published_at = CASE WHEN $8 = 'published' THEN COALESCE($9::timestamp, published_at, NOW()) ELSE $9 END,
+             summary=$10, excerpt=$11, cover_image=$12, seo_description=$13, canonical_url=$14,
+             word_count=$15, reading_minutes=$16,
+             version = version + 1, updated_at=NOW()
+         WHERE id=$17
+         RETURNING COALESCE(published_at, created_at) AS listed_at",
+        title, slug, content_md, rendered.html, Json(&rendered.toc) as _, RENDER_VERSION, &rendered.broken_links,
+        publication.status.as_str(), publication.published_at, summary.as_deref(), rendered.excerpt,
+        cover_image.as_deref(), seo_description.as_deref(), canonical_url.as_deref(),
+        rendered.word_count as i32, rendered.reading_minutes as i32, id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    let url = article_url(id, saved.listed_at, &slug);
+
+    // 回收站中的标签不在表单里，保留其关联以便恢复
+    sqlx::query!(
+        "DELETE FROM article_tags
+         WHERE article_id=$1 AND tag_id NOT IN (SELECT id FROM tags WHERE deleted_at IS NOT NULL)",
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
 
     for &tag_id in tag_ids {
         sqlx::query!("INSERT INTO article_tags (article_id, tag_id) VALUES ($1, $2)", id, tag_id)
@@ -330,44 +645,506 @@ pub async fn update_article(pool: &PgPool, id: i32, title: &str, content_md: &st
             .await?;
     }
 
+    save_article_links(&mut tx, id, &rendered.links).await?;
+    // 每次保存都记一个版本，包括从历史版本恢复
+    save_revision(&mut tx, id, title, content_md).await?;
+
     tx.commit().await?;
+
+    // 标题、地址（slug 或发布年份）或是否发布变了，引用方的链接文字、按标题的链接和链接地址都要更新
+    let was_published = old.status == ArticleStatus::Published.as_str();
+    let is_published = publication.status == ArticleStatus::Published;
+    if old.title != *title || old_url != url || was_published != is_published {
+        rerender_referrers(pool, id, title).await?;
+    }
+    Ok(UpdateResult::Saved)
+}
+
+/// -----------------------------
+/// 回收站
+/// -----------------------------
+/// 移到回收站，前台和站内链接随即失效
+pub async fn trash_article(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("UPDATE articles SET deleted_at = NOW() WHERE id=$1 AND deleted_at IS NULL", id)
+        .execute(pool)
+        .await?;
+
+    // 引用方的链接改为失效
+    let referrers = sqlx::query_scalar!("SELECT source_id FROM article_links WHERE target_id=$1", id)
+        .fetch_all(pool)
+        .await?;
+    for referrer in referrers {
+        rerender_article(pool, referrer).await?;
+    }
     Ok(())
 }
 
-pub async fn delete_article(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
-    let mut tx = pool.begin().await?;
-    sqlx::query!("DELETE FROM article_tags WHERE article_id=$1", id)
-        .execute(&mut *tx)
+/// 文章是否在回收站中；文章不存在时返回 false
+pub async fn is_article_trashed(pool: &PgPool, id: i32) -> Result<bool, sqlx::Error> {
+    let trashed = sqlx::query_scalar!(
+        r#"SELECT deleted_at IS NOT NULL AS "trashed!" FROM articles WHERE id=$1"#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(trashed.unwrap_or_default())
+}
+
+/// 从回收站恢复，失效的引用重新指向本文
+pub async fn restore_article(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    let title = sqlx::query_scalar!(
+        "UPDATE articles SET deleted_at = NULL WHERE id=$1 AND deleted_at IS NOT NULL RETURNING title",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(title) = title {
+        rerender_referrers(pool, id, &title).await?;
+    }
+    Ok(())
+}
+
+/// 永久删除回收站中的文章；标签关联、链接、历史版本等由外键级联删除
+pub async fn purge_article(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM articles WHERE id=$1 AND deleted_at IS NOT NULL", id)
+        .execute(pool)
         .await?;
-    sqlx::query!("DELETE FROM articles WHERE id=$1", id)
-        .execute(&mut *tx)
+    Ok(())
+}
+
+pub async fn trash_tag(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("UPDATE tags SET deleted_at = NOW() WHERE id=$1 AND deleted_at IS NULL", id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn restore_tag(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("UPDATE tags SET deleted_at = NULL WHERE id=$1", id)
+        .execute(pool)
         .await?;
+    Ok(())
+}
+
+pub async fn purge_tag(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM tags WHERE id=$1 AND deleted_at IS NOT NULL", id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// 回收站中的文章和标签，最近删除的在前
+pub async fn get_trash(pool: &PgPool) -> Result<(Vec<TrashItem>, Vec<TrashItem>), sqlx::Error> {
+    let articles = sqlx::query_as!(
+        TrashItem,
+        r#"SELECT id, title AS name, deleted_at AS "deleted_at!"
+           FROM articles WHERE deleted_at IS NOT NULL
+           ORDER BY deleted_at DESC"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let tags = sqlx::query_as!(
+        TrashItem,
+        r#"SELECT id, name, deleted_at AS "deleted_at!"
+           FROM tags WHERE deleted_at IS NOT NULL
+           ORDER BY deleted_at DESC"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok((articles, tags))
+}
+
+/// 永久删除超过保留天数的文章和标签，返回删除的条数；关联数据由外键级联删除
+pub async fn purge_expired_trash(pool: &PgPool, retention_days: i32) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let articles = sqlx::query!(
+        "DELETE FROM articles WHERE deleted_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?;
+    let tags = sqlx::query!(
+        "DELETE FROM tags WHERE deleted_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?;
     tx.commit().await?;
+    Ok(articles.rows_affected() + tags.rows_affected())
+}
+
+/// -----------------------------
+/// 发布到期的定时文章
+/// -----------------------------
+/// 返回发布的篇数
+pub async fn publish_scheduled_articles(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let published = sqlx::query!(
+        "UPDATE articles SET status = 'published'
+         WHERE status = 'scheduled' AND published_at <= NOW()
+         RETURNING id, title"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 此前引用它们的文章中是失效链接
+    for article in &published {
+        rerender_referrers(pool, article.id, &article.title).await?;
+    }
+    Ok(published.len() as u64)
+}
+
+/// -----------------------------
+/// 重新渲染文章 HTML
+/// -----------------------------
+/// `force` 为 false 时只处理渲染版本落后的文章，返回处理的篇数
+pub async fn rerender_articles(pool: &PgPool, force: bool) -> Result<usize, sqlx::Error> {
+    let ids = sqlx::query_scalar!(
+        "SELECT id FROM articles WHERE $1 OR render_version <> $2",
+        force, RENDER_VERSION
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for &id in &ids {
+        rerender_article(pool, id).await?;
+    }
+
+    Ok(ids.len())
+}
+
+/// -----------------------------
+/// 为没有 slug 的旧文章生成 slug
+/// -----------------------------
+pub async fn fill_missing_slugs(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query!("SELECT id, title FROM articles WHERE slug IS NULL ORDER BY id")
+        .fetch_all(pool)
+        .await?;
+
+    for row in &rows {
+        let mut tx = pool.begin().await?;
+        let slug = unique_slug(&mut tx, &row.title, "", Some(row.id)).await?;
+        sqlx::query!("UPDATE articles SET slug=$1 WHERE id=$2", slug, row.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    Ok(rows.len())
+}
+
+/// -----------------------------
+/// 根据 slug 查文章
+/// -----------------------------
+pub async fn get_article_by_slug(pool: &PgPool, slug: &str)
+    -> Result<Article, Error>
+{
+    let id = sqlx::query_scalar!("SELECT id FROM articles WHERE slug=$1 AND deleted_at IS NULL", slug)
+        .fetch_one(pool)
+        .await?;
+    get_article_by_id(id, pool).await
+}
+
+/// 旧 slug 对应的文章 id
+pub async fn get_article_id_by_old_slug(pool: &PgPool, slug: &str)
+    -> Result<Option<i32>, Error>
+{
+    sqlx::query_scalar!("SELECT article_id FROM article_slug_history WHERE slug=$1", slug)
+        .fetch_optional(pool)
+        .await
+}
+
+/// -----------------------------
+/// 草稿预览链接
+/// -----------------------------
+pub async fn create_preview_token(pool: &PgPool, article_id: i32, days: i32) -> Result<String, Error> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    sqlx::query!(
+        "INSERT INTO article_preview_tokens (token, article_id, expires_at)
+         VALUES ($1, $2, NOW() + make_interval(days => $3))",
+        token, article_id, days
+    )
+    .execute(pool)
+    .await?;
+    Ok(token)
+}
+
+pub async fn get_preview_tokens(pool: &PgPool, article_id: i32) -> Result<Vec<PreviewToken>, Error> {
+    sqlx::query_as!(
+        PreviewToken,
+        r#"SELECT token, article_id, expires_at, revoked_at, created_at,
+                  (revoked_at IS NULL AND expires_at > NOW()) AS "active!"
+           FROM article_preview_tokens
+           WHERE article_id=$1
+           ORDER BY created_at DESC"#,
+        article_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn revoke_preview_token(pool: &PgPool, article_id: i32, token: &str) -> Result<(), Error> {
+    sqlx::query!(
+        "UPDATE article_preview_tokens SET revoked_at = NOW()
+         WHERE token=$1 AND article_id=$2 AND revoked_at IS NULL",
+        token, article_id
+    )
+    .execute(pool)
+    .await?;
     Ok(())
 }
 
-// 创建标签
-pub async fn create_tag(pool: &PgPool, name: &str) -> Result<(), sqlx::Error> {
+/// token 有效时返回对应文章；文章在回收站中时 token 视为无效
+pub async fn get_article_by_preview_token(pool: &PgPool, id: i32, token: &str) -> Result<Option<Article>, Error> {
+    let valid = sqlx::query_scalar!(
+        "SELECT 1 FROM article_preview_tokens t
+         JOIN articles a ON a.id = t.article_id AND a.deleted_at IS NULL
+         WHERE t.token=$1 AND t.article_id=$2 AND t.revoked_at IS NULL AND t.expires_at > NOW()",
+        token, id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    match valid {
+        Some(_) => get_article_by_id(id, pool).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// -----------------------------
+/// 文章历史版本
+/// -----------------------------
+/// 按时间倒序，最新的即当前内容
+pub async fn get_article_revisions(pool: &PgPool, article_id: i32) -> Result<Vec<ArticleRevision>, Error> {
+    sqlx::query_as!(
+        ArticleRevision,
+        r#"SELECT id, article_id, title, content_md, created_at,
+                  ROW_NUMBER() OVER (ORDER BY id) AS "number!"
+           FROM article_revisions
+           WHERE article_id=$1
+           ORDER BY id DESC"#,
+        article_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_article_revision(pool: &PgPool, article_id: i32, revision_id: i32) -> Result<ArticleRevision, Error> {
+    sqlx::query_as!(
+        ArticleRevision,
+        r#"SELECT id, article_id, title, content_md, created_at, "number!"
+           FROM (
+               SELECT id, article_id, title, content_md, created_at,
+                      ROW_NUMBER() OVER (ORDER BY id) AS "number!"
+               FROM article_revisions
+               WHERE article_id=$1
+           ) r
+           WHERE id=$2"#,
+        article_id, revision_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// 恢复到某个历史版本，恢复本身也会记为一个新版本
+///
+/// 只恢复标题和正文，slug、状态、标签和摘要等元信息保持不变。
+pub async fn restore_article_revision(pool: &PgPool, article_id: i32, revision_id: i32) -> Result<UpdateResult, Error> {
+    let revision = get_article_revision(pool, article_id, revision_id).await?;
+    let article = get_article_by_id(article_id, pool).await?;
+    let fields = ArticleFields {
+        title: revision.title,
+        slug: article.slug,
+        summary: article.summary,
+        cover_image: article.cover_image,
+        seo_description: article.seo_description,
+        canonical_url: article.canonical_url,
+        content_md: revision.content_md,
+        tag_ids: article.tags,
+        publication: Publication::new(article.status, article.published_at),
+    };
+    update_article(pool, article_id, article.version, &fields).await
+}
+
+/// -----------------------------
+/// 编辑器自动保存
+/// -----------------------------
+/// 覆盖该用户对应文章的自动保存，返回保存时间
+pub async fn save_autosave(pool: &PgPool, user_id: i32, form: &AutosaveForm) -> Result<chrono::NaiveDateTime, Error> {
+    sqlx::query_scalar!(
+        "INSERT INTO article_autosaves (user_id, article_id, title, content_md, tag_ids, saved_at)
+         VALUES ($1, $2, $3, $4, $5, NOW())
+         ON CONFLICT (user_id, COALESCE(article_id, 0))
+         DO UPDATE SET title = EXCLUDED.title, content_md = EXCLUDED.content_md,
+                       tag_ids = EXCLUDED.tag_ids, saved_at = EXCLUDED.saved_at
+         RETURNING saved_at",
+        user_id, form.article_id, form.title, form.content_md, &form.tag_ids
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_autosave(pool: &PgPool, user_id: i32, article_id: Option<i32>) -> Result<Option<Autosave>, Error> {
+    sqlx::query_as!(
+        Autosave,
+        "SELECT article_id, title, content_md, tag_ids, saved_at
+         FROM article_autosaves
+         WHERE user_id=$1 AND article_id IS NOT DISTINCT FROM $2",
+        user_id, article_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_autosave(pool: &PgPool, user_id: i32, article_id: Option<i32>) -> Result<(), Error> {
     sqlx::query!(
-        "INSERT INTO tags (name) VALUES ($1)",
-        name
+        "DELETE FROM article_autosaves WHERE user_id=$1 AND article_id IS NOT DISTINCT FROM $2",
+        user_id, article_id
     )
     .execute(pool)
     .await?;
     Ok(())
 }
 
-// 删除标签
-pub async fn delete_tag(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
-    sqlx::query!("DELETE FROM tags WHERE id=$1", id)
-        .execute(pool)
-        .await?;
+/// -----------------------------
+/// 引用了某篇文章的文章
+/// -----------------------------
+pub async fn get_backlinks(pool: &PgPool, id: i32) -> Result<Vec<RecentArticleView>, Error> {
+    let rows = sqlx::query!(
+        r#"SELECT a.id, a.title, a.slug, a.created_at, COALESCE(a.published_at, a.created_at) AS listed_at,
+                  a.reading_minutes,
+                  COALESCE(NULLIF(a.summary, ''), a.excerpt) AS "excerpt!"
+         FROM article_links l
+         JOIN articles a ON a.id = l.source_id
+         WHERE l.target_id=$1 AND a.status = 'published' AND a.deleted_at IS NULL
+         ORDER BY COALESCE(a.published_at, a.created_at) DESC NULLS LAST, a.id DESC"#,
+        id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| RecentArticleView {
+        url: article_url(row.id, row.listed_at, row.slug.as_deref().unwrap_or_default()),
+        id: row.id,
+        title: row.title,
+        excerpt: row.excerpt,
+        reading_minutes: row.reading_minutes,
+        created_at: row.created_at,
+    }).collect())
+}
+
+/// -----------------------------
+/// 上一篇、下一篇
+/// -----------------------------
+/// 按 `Article::listed_at` 排列的前后两篇已发布文章，与首页、标签页和归档页的顺序一致
+pub async fn get_adjacent_articles(pool: &PgPool, article: &Article)
+    -> Result<(Option<RecentArticleView>, Option<RecentArticleView>), Error>
+{
+    // 两个时间都为空的排在最早，与列表页的 NULLS LAST 对应，也避免与 NULL 比较后查不到任何一篇
+    let rows = sqlx::query!(
+        r#"(SELECT FALSE AS "is_next!", id AS "id!", title AS "title!", slug, created_at,
+                   COALESCE(published_at, created_at) AS listed_at,
+                   reading_minutes AS "reading_minutes!",
+                   COALESCE(NULLIF(summary, ''), excerpt) AS "excerpt!"
+            FROM articles
+            WHERE status = 'published' AND deleted_at IS NULL
+              AND (COALESCE(published_at, created_at, '-infinity'), id) < (COALESCE($2::timestamp, '-infinity'), $1)
+            ORDER BY COALESCE(published_at, created_at, '-infinity') DESC, id DESC
+            LIMIT 1)
+           UNION ALL
+           (SELECT TRUE, id, title, slug, created_at, COALESCE(published_at, created_at), reading_minutes,
+                   COALESCE(NULLIF(summary, ''), excerpt)
+            FROM articles
+            WHERE status = 'published' AND deleted_at IS NULL
+              AND (COALESCE(published_at, created_at, '-infinity'), id) > (COALESCE($2::timestamp, '-infinity'), $1)
+            ORDER BY COALESCE(published_at, created_at, '-infinity'), id
+            LIMIT 1)"#,
+        article.id, article.listed_at()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let (mut prev, mut next) = (None, None);
+    for row in rows {
+        let view = RecentArticleView {
+            url: article_url(row.id, row.listed_at, row.slug.as_deref().unwrap_or_default()),
+            id: row.id,
+            title: row.title,
+            excerpt: row.excerpt,
+            reading_minutes: row.reading_minutes,
+            created_at: row.created_at,
+        };
+        if row.is_next { next = Some(view) } else { prev = Some(view) }
+    }
+    Ok((prev, next))
+}
+
+/// -----------------------------
+/// 相关文章
+/// -----------------------------
+/// 每篇最多取几倍于展示数量的候选
+const RELATED_CANDIDATES_PER_SLOT: i64 = 4;
+
+/// 先在数据库中按共同标签数取出少量候选，再结合标题相似度排序；没有共同标签的不算相关
+pub async fn get_related_articles(pool: &PgPool, article: &Article, limit: usize)
+    -> Result<Vec<RecentArticleView>, Error>
+{
+    let rows = sqlx::query!(
+        r#"SELECT a.id, a.title, a.slug, a.created_at, COALESCE(a.published_at, a.created_at) AS listed_at,
+                  a.reading_minutes,
+                  COALESCE(NULLIF(a.summary, ''), a.excerpt) AS "excerpt!",
+                  COUNT(*) AS "shared_tags!"
+           FROM article_tags mine
+           JOIN tags t ON t.id = mine.tag_id AND t.deleted_at IS NULL
+           JOIN article_tags other ON other.tag_id = mine.tag_id AND other.article_id <> $1
+           JOIN articles a ON a.id = other.article_id AND a.status = 'published' AND a.deleted_at IS NULL
+           WHERE mine.article_id = $1
+           GROUP BY a.id
+           ORDER BY COUNT(*) DESC, a.created_at DESC
+           LIMIT $2"#,
+        article.id, limit as i64 * RELATED_CANDIDATES_PER_SLOT
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 一个共同标签约等于标题一半相似
+    let mut scored: Vec<_> = rows
+        .into_iter()
+        .map(|row| {
+            let score = row.shared_tags as f64 + 2.0 * similarity::title_similarity(&article.title, &row.title);
+            (score, row)
+        })
+        .collect();
+    scored.sort_by(|(a, ra), (b, rb)| b.total_cmp(a).then(rb.created_at.cmp(&ra.created_at)));
+
+    Ok(scored.into_iter().take(limit).map(|(_, row)| RecentArticleView {
+        url: article_url(row.id, row.listed_at, row.slug.as_deref().unwrap_or_default()),
+        id: row.id,
+        title: row.title,
+        excerpt: row.excerpt,
+        reading_minutes: row.reading_minutes,
+        created_at: row.created_at,
+    }).collect())
+}
+
+// 创建标签
+pub async fn create_tag(pool: &PgPool, name: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        // 同名标签在回收站中时直接恢复
+        "INSERT INTO tags (name) VALUES ($1)
+         ON CONFLICT (name) DO UPDATE SET deleted_at = NULL",
+        name
+    )
+    .execute(pool)
+    .await?;
     Ok(())
 }
 
 // 根据 ID 获取单个标签
 pub async fn get_tag_by_id(pool: &PgPool, id: i32) -> Result<Tag, sqlx::Error> {
-    sqlx::query_as!(Tag, "SELECT id, name FROM tags WHERE id=$1", id)
+    sqlx::query_as!(Tag, "SELECT id, name FROM tags WHERE id=$1 AND deleted_at IS NULL", id)
         .fetch_one(pool)
         .await
 }
@@ -380,3 +1157,221 @@ pub async fn increment_visit(pool: &PgPool) -> Result<(), sqlx::Error> {
     .await?;
     Ok(())
 }
+
+/// -----------------------------
+/// 系列
+/// -----------------------------
+pub async fn get_all_series(pool: &PgPool) -> Result<Vec<Series>, sqlx::Error> {
+    sqlx::query_as!(
+        Series,
+        r#"SELECT s.id, s.title, s.description, s.created_at,
+                  (SELECT COUNT(*) FROM series_articles sa
+                   JOIN articles a ON a.id = sa.article_id
+                   WHERE sa.series_id = s.id AND a.deleted_at IS NULL) AS "article_count!"
+           FROM series s
+           ORDER BY s.created_at DESC"#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_series_by_id(pool: &PgPool, id: i32) -> Result<Series, sqlx::Error> {
+    sqlx::query_as!(
+        Series,
+        r#"SELECT s.id, s.title, s.description, s.created_at,
+                  (SELECT COUNT(*) FROM series_articles sa
+                   JOIN articles a ON a.id = sa.article_id
+                   WHERE sa.series_id = s.id AND a.deleted_at IS NULL) AS "article_count!"
+           FROM series s
+           WHERE s.id=$1"#,
+        id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// 系列中的文章，按顺序排列；前台只返回已发布的文章，后台传 `include_hidden` 获取全部
+pub async fn get_series_articles(pool: &PgPool, series_id: i32, include_hidden: bool)
+    -> Result<Vec<SeriesArticle>, sqlx::Error>
+{
+    let rows = sqlx::query!(
+        r#"SELECT a.id, a.title, a.slug, a.status, a.created_at,
+                  COALESCE(a.published_at, a.created_at) AS listed_at
+           FROM series_articles sa
+           JOIN articles a ON a.id = sa.article_id
+           WHERE sa.series_id=$1 AND a.deleted_at IS NULL AND ($2 OR a.status = 'published')
+           ORDER BY sa.position, a.created_at"#,
+        series_id, include_hidden
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| SeriesArticle {
+        url: article_url(row.id, row.listed_at, row.slug.as_deref().unwrap_or_default()),
+        id: row.id,
+        title: row.title,
+        status: ArticleStatus::from_db(&row.status),
+    }).collect())
+}
+
+/// 文章所在系列的导航，文章不属于任何系列时返回 `None`
+pub async fn get_article_series_nav(pool: &PgPool, article_id: i32) -> Result<Option<SeriesNav>, sqlx::Error> {
+    let series = sqlx::query!(
+        "SELECT s.id, s.title FROM series_articles sa JOIN series s ON s.id = sa.series_id WHERE sa.article_id=$1",
+        article_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(series) = series else { return Ok(None) };
+    let articles = get_series_articles(pool, series.id, false).await?;
+    Ok(SeriesNav::new(series.id, series.title, articles, article_id))
+}
+
+/// 新建系列，返回其 id
+pub async fn create_series(pool: &PgPool, form: &SeriesForm) -> Result<i32, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let id = sqlx::query_scalar!(
+        "INSERT INTO series (title, description) VALUES ($1, $2) RETURNING id",
+        form.title.trim(), form.description.trim()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    save_series_articles(&mut tx, id, &form.article_ids).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+pub async fn update_series(pool: &PgPool, id: i32, form: &SeriesForm) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        "UPDATE series SET title=$1, description=$2 WHERE id=$3",
+        form.title.trim(), form.description.trim(), id
+    )
+    .execute(&mut *tx)
+    .await?;
+    save_series_articles(&mut tx, id, &form.article_ids).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 按给定顺序保存系列中的文章
+///
+/// 文章原先属于其他系列时移到本系列；回收站中的文章不在表单里，保留其位置以便恢复。
+async fn save_series_articles(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    series_id: i32,
+    article_ids: &[i32],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM series_articles
+         WHERE series_id=$1 AND article_id NOT IN (SELECT id FROM articles WHERE deleted_at IS NOT NULL)",
+        series_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    for (position, &article_id) in (1..).zip(article_ids) {
+        sqlx::query!(
+            "INSERT INTO series_articles (article_id, series_id, position) VALUES ($1, $2, $3)
+             ON CONFLICT (article_id) DO UPDATE SET series_id = EXCLUDED.series_id, position = EXCLUDED.position",
+            article_id, series_id, position
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// 删除系列，其中的文章不受影响
+pub async fn delete_series(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM series WHERE id=$1", id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(title: &str, publication: Publication) -> ArticleFields {
+        ArticleFields {
+            title: title.to_string(),
+            slug: String::new(),
+            summary: None,
+            cover_image: None,
+            seo_description: None,
+            canonical_url: None,
+            content_md: format!("{}的正文", title),
+            tag_ids: vec![],
+            publication,
+        }
+    }
+
+    async fn article_id(pool: &PgPool, title: &str) -> i32 {
+        sqlx::query_scalar!("SELECT id FROM articles WHERE title=$1", title)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// 先写好、很久之后才定时发布的文章，在列表页和上一篇、下一篇中都按发布时间排
+    #[sqlx::test]
+    async fn listings_and_adjacent_articles_agree_on_publish_order(pool: PgPool) -> sqlx::Result<()> {
+        let published = Publication::new(ArticleStatus::Published, None);
+        create_article(&pool, &fields("较早", published)).await?;
+        create_article(&pool, &fields("较晚", published)).await?;
+        let future = chrono::NaiveDate::from_ymd_opt(2100, 1, 1).unwrap().and_hms_opt(0, 0, 0);
+        create_article(&pool, &fields("定时", Publication::new(ArticleStatus::Scheduled, future))).await?;
+
+        for (title, days) in [("较早", 10), ("较晚", 5)] {
+            sqlx::query!(
+                "UPDATE articles SET created_at = NOW() - make_interval(days => $2),
+                                     published_at = NOW() - make_interval(days => $2)
+                 WHERE title = $1",
+                title, days
+            )
+            .execute(&pool)
+            .await?;
+        }
+        // 草稿早在一个月前写好，到了发布时间才上线
+        sqlx::query!(
+            "UPDATE articles SET created_at = NOW() - INTERVAL '30 days', published_at = NOW() - INTERVAL '1 minute'
+             WHERE title = '定时'"
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(publish_scheduled_articles(&pool).await?, 1);
+
+        let expected = vec![
+            article_id(&pool, "定时").await,
+            article_id(&pool, "较晚").await,
+            article_id(&pool, "较早").await,
+        ];
+
+        let recent: Vec<i32> = get_recent_articles(&pool, 10).await?.iter().map(|a| a.id).collect();
+        assert_eq!(recent, expected);
+        let all: Vec<i32> = get_all_articles(&pool, false).await?.iter().map(|a| a.id).collect();
+        assert_eq!(all, expected);
+        let archive: Vec<i32> = get_articles_grouped_by_year(&pool)
+            .await?
+            .into_iter()
+            .flat_map(|(_, articles)| articles)
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(archive, expected);
+
+        // 列表中相邻的两篇互为上一篇、下一篇
+        for pair in expected.windows(2) {
+            let newer = get_article_by_id(pair[0], &pool).await?;
+            let (prev, _) = get_adjacent_articles(&pool, &newer).await?;
+            assert_eq!(prev.map(|a| a.id), Some(pair[1]));
+
+            let older = get_article_by_id(pair[1], &pool).await?;
+            let (_, next) = get_adjacent_articles(&pool, &older).await?;
+            assert_eq!(next.map(|a| a.id), Some(pair[0]));
+        }
+        Ok(())
+    }
+}
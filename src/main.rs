@@ -4,6 +4,8 @@ extern crate rocket;
 mod db;
 mod routes;
 mod models;
+mod site;
+mod utils;
 
 use dotenvy::dotenv;
 use rocket::{Rocket, Build};
@@ -16,6 +18,7 @@ use rocket::outcome::Outcome;
 
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
+use std::time::Duration;
 
 use routes::*;
 use models::{User as UserModel};
@@ -90,6 +93,54 @@ async fn rocket() -> Rocket<Build> {
         .await
         .expect("Failed to create database pool");
 
+    // 执行数据库迁移
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("Failed to run database migrations");
+
+    // 为旧文章生成 slug
+    match db::fill_missing_slugs(&pool).await {
+        Ok(0) => {}
+        Ok(n) => println!("已为 {} 篇文章生成 slug", n),
+        Err(e) => eprintln!("生成文章 slug 失败: {:?}", e),
+    }
+
+    // 渲染管线升级后，重新渲染旧版本的文章
+    match db::rerender_articles(&pool, false).await {
+        Ok(0) => {}
+        Ok(n) => println!("已重新渲染 {} 篇文章", n),
+        Err(e) => eprintln!("重新渲染文章失败: {:?}", e),
+    }
+
+    // 定时发布：启动时先处理一次，之后每分钟检查
+    let scheduler_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            match db::publish_scheduled_articles(&scheduler_pool).await {
+                Ok(0) => {}
+                Ok(n) => println!("已发布 {} 篇定时文章", n),
+                Err(e) => eprintln!("发布定时文章失败: {:?}", e),
+            }
+        }
+    });
+
+    // 回收站：每小时清理超过保留期的文章和标签
+    let purge_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            match db::purge_expired_trash(&purge_pool, site::trash_retention_days()).await {
+                Ok(0) => {}
+                Ok(n) => println!("已从回收站永久删除 {} 项", n),
+                Err(e) => eprintln!("清理回收站失败: {:?}", e),
+            }
+        }
+    });
+
     // 创建 session 存储
     let sessions: SessionStore = Arc::new(Mutex::new(HashMap::new()));
 
@@ -103,7 +154,9 @@ async fn rocket() -> Rocket<Build> {
         .mount("/friends", routes![friends::friends])
         .mount("/travel", routes![travel::travel])
         .mount("/article", routes![article::article])
+        .mount("/posts", routes![article::post])
         .mount("/tags", routes![tags::tags, tags::tag_articles])
+        .mount("/series", routes![series::series])
         .mount("/archive", routes![archive::archive])
         .mount("/static", FileServer::from("static"))
         // 后台 Admin 路由
@@ -118,10 +171,29 @@ async fn rocket() -> Rocket<Build> {
             admin::edit_article_page,
             admin::update_article,
             admin::delete_article,
+            admin::preview,
+            admin::autosave,
+            admin::discard_autosave,
+            admin::create_preview_token,
+            admin::revoke_preview_token,
+            admin::article_revisions_page,
+            admin::article_revision_diff,
+            admin::restore_article_revision,
+            admin::rerender_articles,
             admin::tags_page,
             admin::new_tag_page,
             admin::create_tag,
             admin::delete_tag,
+            admin::series_page,
+            admin::create_series,
+            admin::edit_series_page,
+            admin::update_series,
+            admin::delete_series,
+            admin::trash_page,
+            admin::restore_article,
+            admin::purge_article,
+            admin::restore_tag,
+            admin::purge_tag,
             admin::articles_data,
         ])
 }
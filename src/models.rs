@@ -1,19 +1,131 @@
 use sqlx::FromRow;
-use chrono::NaiveDateTime;
-use serde::Serialize;
-use rocket::form::FromForm;
+use chrono::{Datelike, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use rocket::form::{FromForm, FromFormField};
+use crate::utils::markdown::TocItem;
 
 /// 文章模型
 #[derive(FromRow, Debug, Serialize, Clone)]
 pub struct Article {
     pub id: i32,
     pub title: String,
+    /// URL 中使用的 slug，见 `utils::slug`
+    pub slug: String,
+    /// 永久链接，见 `article_url`
+    pub url: String,
     pub content_md: String,
+    /// 预渲染的 HTML，保存时生成
+    pub content_html: String,
+    /// 预渲染的目录
+    pub toc: Vec<TocItem>,
+    /// 生成 content_html 时的渲染管线版本
+    pub render_version: i32,
+    /// 找不到目标的维基链接
+    pub broken_links: Vec<String>,
+    pub status: ArticleStatus,
+    /// 发布时间；定时发布的文章在此时间自动发布
+    pub published_at: Option<NaiveDateTime>,
+    /// 每次保存加一，用于检测编辑冲突
+    pub version: i32,
+    /// 手写摘要
+    pub summary: Option<String>,
+    /// 列表页展示的摘要：有手写摘要时为手写摘要，否则为自动摘要
+    pub excerpt: String,
+    /// 封面图地址，站内图片为 `/` 开头的路径
+    pub cover_image: Option<String>,
+    /// 搜索引擎和分享卡片的描述，为空时使用 `excerpt`
+    pub seo_description: Option<String>,
+    /// 规范链接，转载文章可指向原文，为空时为本文地址
+    pub canonical_url: Option<String>,
+    /// 字数，中文按字、英文按词计，不含代码块
+    pub word_count: i32,
+    /// 预计阅读分钟数
+    pub reading_minutes: i32,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
     pub tags: Vec<i32>,
 }
 
+impl Article {
+    /// 列表和上一篇、下一篇的排序时间：发布时间，没有时用创建时间
+    pub fn listed_at(&self) -> Option<NaiveDateTime> {
+        self.published_at.or(self.created_at)
+    }
+}
+
+/// 文章状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, FromFormField)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleStatus {
+    /// 草稿，仅后台可见
+    #[field(value = "draft")]
+    Draft,
+    /// 已发布
+    #[field(value = "published")]
+    Published,
+    /// 定时发布，到 `published_at` 后由后台任务改为已发布
+    #[field(value = "scheduled")]
+    Scheduled,
+    /// 不出现在列表中，但可以通过链接访问
+    #[field(value = "unlisted")]
+    Unlisted,
+}
+
+impl ArticleStatus {
+    /// 数据库中的取值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ArticleStatus::Draft => "draft",
+            ArticleStatus::Published => "published",
+            ArticleStatus::Scheduled => "scheduled",
+            ArticleStatus::Unlisted => "unlisted",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "draft" => ArticleStatus::Draft,
+            "scheduled" => ArticleStatus::Scheduled,
+            "unlisted" => ArticleStatus::Unlisted,
+            _ => ArticleStatus::Published,
+        }
+    }
+
+    /// 前台能否通过链接访问
+    pub fn is_public(self) -> bool {
+        matches!(self, ArticleStatus::Published | ArticleStatus::Unlisted)
+    }
+}
+
+/// 文章的发布设置
+#[derive(Debug, Clone, Copy)]
+pub struct Publication {
+    pub status: ArticleStatus,
+    pub published_at: Option<NaiveDateTime>,
+}
+
+impl Publication {
+    /// 定时发布但没有填写时间的，按草稿保存
+    pub fn new(status: ArticleStatus, published_at: Option<NaiveDateTime>) -> Self {
+        let status = match (status, published_at) {
+            (ArticleStatus::Scheduled, None) => ArticleStatus::Draft,
+            (status, _) => status,
+        };
+        Publication { status, published_at }
+    }
+}
+
+/// 文章永久链接：`/posts/<发布年份>/<slug>`
+///
+/// `listed_at` 为 `Article::listed_at`，未发布过的文章用创建年份；
+/// 两个时间都没有时无法确定年份，退回 `/article/<id>`
+pub fn article_url(id: i32, listed_at: Option<NaiveDateTime>, slug: &str) -> String {
+    match listed_at {
+        Some(dt) => format!("/posts/{}/{}", dt.year(), slug),
+        None => format!("/article/{}", id),
+    }
+}
+
 /// 标签模型
 #[derive(FromRow, Debug, Serialize, Clone)]
 pub struct Tag {
@@ -42,8 +154,69 @@ pub struct UserLoginForm {
 #[derive(FromForm, Debug)]
 pub struct NewArticleForm {
     pub title: String,
+    /// 留空则由标题自动生成
+    pub slug: Option<String>,
+    #[field(default = ArticleStatus::Published)]
+    pub status: ArticleStatus,
+    /// `<input type="datetime-local">` 的值，如 `2026-10-17T08:00`
+    pub published_at: Option<String>,
+    /// 编辑时打开页面时的文章版本号，新建时为空
+    pub version: Option<i32>,
+    /// 手写摘要，留空则使用自动摘要
+    pub summary: Option<String>,
+    pub cover_image: Option<String>,
+    pub seo_description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub content_md: String,
+    pub tag_ids: Vec<i32>,
+}
+
+/// 去掉首尾空白，空字符串视为未填写
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+/// 解析 `<input type="datetime-local">` 的值
+fn parse_datetime_local(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+}
+
+impl NewArticleForm {
+    /// 转换为保存文章所需的内容
+    pub fn fields(&self) -> ArticleFields {
+        ArticleFields {
+            title: self.title.clone(),
+            slug: self.slug.clone().unwrap_or_default(),
+            summary: non_empty(&self.summary),
+            cover_image: non_empty(&self.cover_image),
+            seo_description: non_empty(&self.seo_description),
+            canonical_url: non_empty(&self.canonical_url),
+            content_md: self.content_md.clone(),
+            tag_ids: self.tag_ids.clone(),
+            publication: Publication::new(
+                self.status,
+                self.published_at.as_deref().and_then(parse_datetime_local),
+            ),
+        }
+    }
+}
+
+/// 新建、更新文章时保存的内容
+#[derive(Debug)]
+pub struct ArticleFields {
+    pub title: String,
+    /// 为空则由标题自动生成
+    pub slug: String,
+    pub summary: Option<String>,
+    pub cover_image: Option<String>,
+    pub seo_description: Option<String>,
+    pub canonical_url: Option<String>,
     pub content_md: String,
     pub tag_ids: Vec<i32>,
+    pub publication: Publication,
 }
 
 /// 新标签表单
@@ -52,12 +225,134 @@ pub struct NewTagForm {
     pub name: String,
 }
 
+/// 草稿预览链接
+#[derive(FromRow, Debug, Serialize)]
+pub struct PreviewToken {
+    pub token: String,
+    pub article_id: i32,
+    pub expires_at: NaiveDateTime,
+    /// 手动撤销的时间
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    /// 未撤销且未过期
+    pub active: bool,
+}
+
+/// 文章历史版本
+#[derive(FromRow, Debug, Serialize)]
+pub struct ArticleRevision {
+    pub id: i32,
+    pub article_id: i32,
+    /// 该文章的第几个版本，从 1 开始
+    pub number: i64,
+    pub title: String,
+    pub content_md: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// 回收站条目（文章或标签）
+#[derive(FromRow, Debug, Serialize)]
+pub struct TrashItem {
+    pub id: i32,
+    /// 文章标题或标签名
+    pub name: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// 编辑器自动保存的内容
+#[derive(FromRow, Debug, Serialize)]
+pub struct Autosave {
+    pub article_id: Option<i32>,
+    pub title: String,
+    pub content_md: String,
+    pub tag_ids: Vec<i32>,
+    pub saved_at: NaiveDateTime,
+}
+
+/// 自动保存请求，`article_id` 为空表示新建文章
+#[derive(Debug, Deserialize)]
+pub struct AutosaveForm {
+    pub article_id: Option<i32>,
+    pub title: String,
+    pub content_md: String,
+    #[serde(default)]
+    pub tag_ids: Vec<i32>,
+}
+
+/// 生成预览链接表单
+#[derive(FromForm, Debug)]
+pub struct NewPreviewTokenForm {
+    /// 有效天数
+    #[field(validate = range(1..=30))]
+    pub days: i32,
+}
+
+/// 系列
+#[derive(FromRow, Debug, Serialize)]
+pub struct Series {
+    pub id: i32,
+    pub title: String,
+    pub description: String,
+    /// 系列中不在回收站的文章数（含未发布）
+    pub article_count: i64,
+    pub created_at: NaiveDateTime,
+}
+
+/// 系列中的一篇文章
+#[derive(Debug, Serialize, Clone)]
+pub struct SeriesArticle {
+    pub id: i32,
+    pub title: String,
+    pub url: String,
+    pub status: ArticleStatus,
+}
+
+/// 文章页的系列导航：“第 N 篇，共 M 篇”和上一篇、下一篇
+#[derive(Debug, Serialize)]
+pub struct SeriesNav {
+    pub id: i32,
+    pub title: String,
+    /// 从 1 开始，只计已发布的文章
+    pub position: usize,
+    pub articles: Vec<SeriesArticle>,
+    pub prev: Option<SeriesArticle>,
+    pub next: Option<SeriesArticle>,
+}
+
+impl SeriesNav {
+    /// 由系列中已发布的文章生成导航，文章不在其中时返回 `None`
+    pub fn new(id: i32, title: String, articles: Vec<SeriesArticle>, article_id: i32) -> Option<Self> {
+        let index = articles.iter().position(|a| a.id == article_id)?;
+        Some(SeriesNav {
+            id,
+            title,
+            position: index + 1,
+            prev: index.checked_sub(1).map(|i| articles[i].clone()),
+            next: articles.get(index + 1).cloned(),
+            articles,
+        })
+    }
+}
+
+/// 新建、编辑系列表单
+#[derive(FromForm, Debug)]
+pub struct SeriesForm {
+    pub title: String,
+    #[field(default = String::new())]
+    pub description: String,
+    /// 系列中的文章，按顺序排列
+    pub article_ids: Vec<i32>,
+}
 
 /// 首页视图结构
 #[derive(FromRow, Debug, Serialize)]
 pub struct RecentArticleView {
     pub id: i32,
     pub title: String,
+    pub url: String,
+    /// 摘要，同 `Article::excerpt`
+    pub excerpt: String,
+    pub reading_minutes: i32,
     pub created_at: Option<NaiveDateTime>,
 }
 
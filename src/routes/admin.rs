@@ -1,15 +1,17 @@
 use rocket_dyn_templates::Template;
 use rocket::response::Redirect;
 use rocket_dyn_templates::context;
-use rocket::{get, post, form::Form, State};
+use rocket::{delete, get, post, form::Form, State};
 use crate::db;
-use crate::models::{UserLoginForm, NewArticleForm, NewTagForm, User as UserModel};
+use crate::site;
+use crate::utils::diff;
+use crate::models::{AutosaveForm, UserLoginForm, NewArticleForm, NewTagForm, NewPreviewTokenForm, SeriesForm, ArticleStatus, User as UserModel};
 use sqlx::PgPool;
 use rocket::request::{self, FromRequest, Request};
 use rocket::http::{Status, Cookie, CookieJar};
 use rocket::outcome::Outcome;
 use rocket::serde::json::Json;
-use rocket::serde::Serialize;
+use rocket::serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -145,17 +147,19 @@ pub async fn dashboard(_admin: AdminGuard) -> Template {
 pub struct ArticleJson {
     id: i32,
     title: String,
+    url: String,
     content_md: String,
     created_at: String,
 }
 
 #[get("/articles_data")]
 pub async fn articles_data(_admin: AdminGuard, pool: &State<PgPool>) -> Json<Vec<ArticleJson>> {
-    let articles = db::get_all_articles(pool.inner()).await.unwrap_or_default();
+    let articles = db::get_all_articles(pool.inner(), true).await.unwrap_or_default();
     
     let data: Vec<ArticleJson> = articles.into_iter().map(|a| ArticleJson {
         id: a.id,
         title: a.title,
+        url: a.url,
         content_md: a.content_md,
         created_at: a.created_at
             .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
@@ -167,63 +171,323 @@ pub async fn articles_data(_admin: AdminGuard, pool: &State<PgPool>) -> Json<Vec
 
 #[get("/articles")]
 pub async fn articles_page(_admin: AdminGuard, pool: &State<PgPool>) -> Template {
-    let articles = db::get_all_articles(pool.inner()).await.unwrap_or_default();
+    let articles = db::get_all_articles(pool.inner(), true).await.unwrap_or_default();
     Template::render("admin/articles", context! { articles })
 }
 
 #[get("/articles/new")]
-pub async fn new_article_page(_admin: AdminGuard, pool: &State<PgPool>) -> Template {
+pub async fn new_article_page(admin: AdminGuard, pool: &State<PgPool>) -> Template {
     let tags = db::get_all_tags(pool.inner()).await.unwrap_or_default();
-    Template::render("admin/new_article", context! { tags })
+    // 上次没写完的新文章
+    let autosave = db::get_autosave(pool.inner(), admin.0.id, None).await.unwrap_or_default();
+    Template::render("admin/new_article", context! { tags, autosave })
 }
 
 #[post("/articles", data = "<form>")]
-pub async fn create_article(_admin: AdminGuard, form: Form<NewArticleForm>, pool: &State<PgPool>) -> Redirect {
-    let NewArticleForm { title, content_md, tag_ids } = form.into_inner();
-    let _ = db::create_article(pool.inner(), &title, &content_md, &tag_ids).await;
+pub async fn create_article(admin: AdminGuard, form: Form<NewArticleForm>, pool: &State<PgPool>) -> Redirect {
+    match db::create_article(pool.inner(), &form.fields()).await {
+        Ok(()) => {
+            let _ = db::delete_autosave(pool.inner(), admin.0.id, None).await;
+        }
+        Err(e) => eprintln!("创建文章失败: {:?}", e),
+    }
     Redirect::to("/admin/articles")
 }
 
 #[get("/articles/<id>/edit")]
-pub async fn edit_article_page(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Template {
+pub async fn edit_article_page(admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Template {
+    if let Some(notice) = trashed_notice(pool.inner(), id).await {
+        return notice;
+    }
+
     // 1. 获取文章及其标签
     let article: crate::models::Article = match db::get_article_by_id(id, pool.inner()).await {
         Ok(a) => a,
         Err(_) => return Template::render("error", context! { message: "文章不存在" }),
     };
 
-    // 2. 包装成新的结构体传给模板（可选，因为现在 tags 已经是 Vec<i32>）
-    #[derive(Serialize)]
-    struct ArticleWithTagIds<'a> {
-        id: i32,
-        title: &'a str,
-        content_md: &'a str,
-        tag_ids: Vec<i32>,
-    }
+    // 2. 比已保存内容更新的自动保存
+    let autosave = db::get_autosave(pool.inner(), admin.0.id, Some(id))
+        .await
+        .unwrap_or_default()
+        .filter(|a| Some(a.saved_at) > article.updated_at)
+        .filter(|a| a.title != article.title || a.content_md != article.content_md || a.tag_ids != article.tags);
 
-    let article_with_ids = ArticleWithTagIds {
-        id: article.id,
-        title: &article.title,
-        content_md: &article.content_md,
-        tag_ids: article.tags.clone(), // 🔥 直接 clone
-    };
+    render_edit_page(pool.inner(), EditableArticle::from_article(&article), autosave, None).await
+}
 
-    // 3. 获取所有标签
-    let tags = db::get_all_tags(pool.inner()).await.unwrap_or_default();
+/// 编辑页表单回填的内容
+#[derive(Serialize)]
+struct EditableArticle {
+    id: i32,
+    title: String,
+    slug: String,
+    summary: String,
+    cover_image: String,
+    seo_description: String,
+    canonical_url: String,
+    content_md: String,
+    tag_ids: Vec<i32>,
+    broken_links: Vec<String>,
+    status: ArticleStatus,
+    /// 供 `datetime-local` 输入框回填
+    published_at: String,
+    version: i32,
+}
 
-    Template::render("admin/edit_article", context! { article: article_with_ids, tags })
+impl EditableArticle {
+    fn from_article(article: &crate::models::Article) -> Self {
+        EditableArticle {
+            id: article.id,
+            title: article.title.clone(),
+            slug: article.slug.clone(),
+            summary: article.summary.clone().unwrap_or_default(),
+            cover_image: article.cover_image.clone().unwrap_or_default(),
+            seo_description: article.seo_description.clone().unwrap_or_default(),
+            canonical_url: article.canonical_url.clone().unwrap_or_default(),
+            content_md: article.content_md.clone(),
+            tag_ids: article.tags.clone(),
+            broken_links: article.broken_links.clone(),
+            status: article.status,
+            published_at: article.published_at
+                .map(|dt| dt.format("%Y-%m-%dT%H:%M").to_string())
+                .unwrap_or_default(),
+            version: article.version,
+        }
+    }
+
+    /// 保存失败时原样回填提交的内容，版本号不变，重新提交时照常检查冲突
+    fn from_form(id: i32, form: NewArticleForm) -> Self {
+        EditableArticle {
+            id,
+            title: form.title,
+            slug: form.slug.unwrap_or_default(),
+            summary: form.summary.unwrap_or_default(),
+            cover_image: form.cover_image.unwrap_or_default(),
+            seo_description: form.seo_description.unwrap_or_default(),
+            canonical_url: form.canonical_url.unwrap_or_default(),
+            content_md: form.content_md,
+            tag_ids: form.tag_ids,
+            broken_links: vec![],
+            status: form.status,
+            published_at: form.published_at.unwrap_or_default(),
+            version: form.version.unwrap_or_default(),
+        }
+    }
+}
+
+async fn render_edit_page(
+    pool: &PgPool,
+    article: EditableArticle,
+    autosave: Option<crate::models::Autosave>,
+    error: Option<String>,
+) -> Template {
+    let tags = db::get_all_tags(pool).await.unwrap_or_default();
+    // 草稿预览链接
+    let preview_tokens = db::get_preview_tokens(pool, article.id).await.unwrap_or_default();
+    Template::render("admin/edit_article", context! { article, tags, preview_tokens, autosave, error })
 }
 
 #[post("/articles/<id>", data = "<form>")]
-pub async fn update_article(_admin: AdminGuard, id: i32, form: Form<NewArticleForm>, pool: &State<PgPool>) -> Redirect {
-    let NewArticleForm { title, content_md, tag_ids } = form.into_inner();
-    let _ = db::update_article(pool.inner(), id, &title, &content_md, &tag_ids).await;
-    Redirect::to("/admin/articles")
+pub async fn update_article(admin: AdminGuard, id: i32, form: Form<NewArticleForm>, pool: &State<PgPool>) -> Result<Redirect, Template> {
+    // 编辑页打开期间文章可能已被删除
+    if let Some(notice) = trashed_notice(pool.inner(), id).await {
+        return Err(notice);
+    }
+
+    // 没有版本号的请求一律视为冲突，交给人工确认
+    let result = db::update_article(pool.inner(), id, form.version.unwrap_or_default(), &form.fields()).await;
+
+    let current = match result {
+        Ok(db::UpdateResult::Saved) => {
+            let _ = db::delete_autosave(pool.inner(), admin.0.id, Some(id)).await;
+            return Ok(Redirect::to("/admin/articles"));
+        }
+        Ok(db::UpdateResult::Conflict(current)) => current,
+        Err(e) => {
+            // 留在编辑页并回填本次提交的内容，避免修改丢失
+            eprintln!("更新文章失败: {:?}", e);
+            let error = format!("保存失败，修改尚未保存，请稍后重试：{}", e);
+            return Err(render_edit_page(pool.inner(), EditableArticle::from_form(id, form.into_inner()), None, Some(error)).await);
+        }
+    };
+
+    // 冲突页：对比数据库中的版本和本次提交的内容，可选择覆盖或放弃
+    #[derive(Serialize)]
+    struct SubmittedArticle {
+        title: String,
+        slug: String,
+        summary: String,
+        cover_image: String,
+        seo_description: String,
+        canonical_url: String,
+        status: ArticleStatus,
+        published_at: String,
+        content_md: String,
+        tag_ids: Vec<i32>,
+    }
+
+    let NewArticleForm {
+        title, slug, summary, cover_image, seo_description, canonical_url, status, published_at, content_md, tag_ids, ..
+    } = form.into_inner();
+    let hunks = diff::line_diff(&current.content_md, &content_md);
+    Err(Template::render("admin/article_conflict", context! {
+        current,
+        mine: SubmittedArticle {
+            title,
+            slug: slug.unwrap_or_default(),
+            summary: summary.unwrap_or_default(),
+            cover_image: cover_image.unwrap_or_default(),
+            seo_description: seo_description.unwrap_or_default(),
+            canonical_url: canonical_url.unwrap_or_default(),
+            status,
+            published_at: published_at.unwrap_or_default(),
+            content_md,
+            tag_ids,
+        },
+        hunks,
+    }))
 }
 
+// 删除文章：移到回收站
 #[post("/articles/<id>/delete")]
 pub async fn delete_article(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
-    let _ = db::delete_article(pool.inner(), id).await;
+    if let Err(e) = db::trash_article(pool.inner(), id).await {
+        eprintln!("删除文章失败: {:?}", e);
+    }
+    Redirect::to("/admin/articles")
+}
+
+//------------------------------------
+// 编辑器实时预览
+//------------------------------------
+#[derive(Deserialize)]
+pub struct PreviewForm {
+    content_md: String,
+}
+
+// 与保存文章时完全相同的渲染流程（含清洗、目录和代码高亮），返回 HTML 片段
+#[post("/preview", format = "json", data = "<form>")]
+pub async fn preview(_admin: AdminGuard, form: Json<PreviewForm>, pool: &State<PgPool>) -> Result<Template, Status> {
+    match db::render_article_content(pool.inner(), &form.content_md).await {
+        Ok(rendered) => Ok(Template::render("admin/preview", context! {
+            html: rendered.html,
+            toc: rendered.toc,
+            broken_links: rendered.broken_links,
+        })),
+        Err(e) => {
+            eprintln!("预览渲染失败: {:?}", e);
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+//------------------------------------
+// 编辑器自动保存
+//------------------------------------
+#[derive(Serialize)]
+pub struct AutosaveStatus {
+    saved_at: String,
+}
+
+#[post("/autosave", format = "json", data = "<form>")]
+pub async fn autosave(admin: AdminGuard, form: Json<AutosaveForm>, pool: &State<PgPool>) -> Result<Json<AutosaveStatus>, Status> {
+    match db::save_autosave(pool.inner(), admin.0.id, &form).await {
+        Ok(saved_at) => Ok(Json(AutosaveStatus {
+            saved_at: saved_at.format("%H:%M:%S").to_string(),
+        })),
+        Err(e) => {
+            eprintln!("自动保存失败: {:?}", e);
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+// 丢弃自动保存
+#[delete("/autosave?<article_id>")]
+pub async fn discard_autosave(admin: AdminGuard, article_id: Option<i32>, pool: &State<PgPool>) -> Status {
+    match db::delete_autosave(pool.inner(), admin.0.id, article_id).await {
+        Ok(()) => Status::NoContent,
+        Err(_) => Status::InternalServerError,
+    }
+}
+
+// 生成草稿预览链接
+#[post("/articles/<id>/previews", data = "<form>")]
+pub async fn create_preview_token(_admin: AdminGuard, id: i32, form: Form<NewPreviewTokenForm>, pool: &State<PgPool>) -> Result<Redirect, Template> {
+    if let Some(notice) = trashed_notice(pool.inner(), id).await {
+        return Err(notice);
+    }
+    if let Err(e) = db::create_preview_token(pool.inner(), id, form.days).await {
+        eprintln!("生成预览链接失败: {:?}", e);
+    }
+    Ok(Redirect::to(format!("/admin/articles/{}/edit#previews", id)))
+}
+
+// 撤销草稿预览链接
+#[post("/articles/<id>/previews/<token>/revoke")]
+pub async fn revoke_preview_token(_admin: AdminGuard, id: i32, token: &str, pool: &State<PgPool>) -> Redirect {
+    let _ = db::revoke_preview_token(pool.inner(), id, token).await;
+    Redirect::to(format!("/admin/articles/{}/edit#previews", id))
+}
+
+// 文章历史版本列表
+#[get("/articles/<id>/revisions")]
+pub async fn article_revisions_page(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Template {
+    if let Some(notice) = trashed_notice(pool.inner(), id).await {
+        return notice;
+    }
+    render_revisions(pool.inner(), id, None).await
+}
+
+async fn render_revisions(pool: &PgPool, id: i32, error: Option<&str>) -> Template {
+    let article = match db::get_article_by_id(id, pool).await {
+        Ok(a) => a,
+        Err(_) => return Template::render("error", context! { message: "文章不存在" }),
+    };
+    let revisions = db::get_article_revisions(pool, id).await.unwrap_or_default();
+    Template::render("admin/revisions", context! { article, revisions, error })
+}
+
+// 对比两个历史版本
+#[get("/articles/<id>/revisions/diff?<from>&<to>")]
+pub async fn article_revision_diff(_admin: AdminGuard, id: i32, from: i32, to: i32, pool: &State<PgPool>) -> Template {
+    let (old, new) = match (
+        db::get_article_revision(pool.inner(), id, from).await,
+        db::get_article_revision(pool.inner(), id, to).await,
+    ) {
+        (Ok(old), Ok(new)) => (old, new),
+        _ => return Template::render("error", context! { message: "版本不存在" }),
+    };
+    // 总是旧版本在左
+    let (old, new) = if old.id <= new.id { (old, new) } else { (new, old) };
+    let hunks = diff::line_diff(&old.content_md, &new.content_md);
+    Template::render("admin/revision_diff", context! { article_id: id, old, new, hunks })
+}
+
+// 恢复到历史版本
+#[post("/articles/<id>/revisions/<revision_id>/restore")]
+pub async fn restore_article_revision(_admin: AdminGuard, id: i32, revision_id: i32, pool: &State<PgPool>) -> Result<Redirect, Template> {
+    if let Some(notice) = trashed_notice(pool.inner(), id).await {
+        return Err(notice);
+    }
+    let error = match db::restore_article_revision(pool.inner(), id, revision_id).await {
+        Ok(db::UpdateResult::Saved) => return Ok(Redirect::to(format!("/admin/articles/{}/revisions", id))),
+        Ok(db::UpdateResult::Conflict(_)) => "恢复失败：文章刚刚被其他人修改，请查看最新版本后重试".to_string(),
+        Err(e) => {
+            eprintln!("恢复历史版本失败: {:?}", e);
+            format!("恢复失败，文章内容未改变：{}", e)
+        }
+    };
+    Err(render_revisions(pool.inner(), id, Some(&error)).await)
+}
+
+// 渲染管线升级后手动触发，重新渲染全部文章
+#[post("/articles/rerender")]
+pub async fn rerender_articles(_admin: AdminGuard, pool: &State<PgPool>) -> Redirect {
+    if let Err(e) = db::rerender_articles(pool.inner(), true).await {
+        eprintln!("重新渲染文章失败: {:?}", e);
+    }
     Redirect::to("/admin/articles")
 }
 
@@ -264,8 +528,110 @@ pub async fn create_tag(
 }
 
 
+// 删除标签：移到回收站
 #[post("/tags/<id>/delete")]
 pub async fn delete_tag(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
-    let _ = db::delete_tag(pool.inner(), id).await;
+    let _ = db::trash_tag(pool.inner(), id).await;
     Redirect::to("/admin/tags")
 }
+
+//------------------------------------
+// 系列
+//------------------------------------
+#[get("/series")]
+pub async fn series_page(_admin: AdminGuard, pool: &State<PgPool>) -> Template {
+    let series = db::get_all_series(pool.inner()).await.unwrap_or_default();
+    Template::render("admin/series", context! { series })
+}
+
+#[post("/series", data = "<form>")]
+pub async fn create_series(_admin: AdminGuard, form: Form<SeriesForm>, pool: &State<PgPool>) -> Redirect {
+    match db::create_series(pool.inner(), &form).await {
+        // 创建后进入编辑页添加文章
+        Ok(id) => Redirect::to(format!("/admin/series/{}", id)),
+        Err(e) => {
+            eprintln!("创建系列失败: {:?}", e);
+            Redirect::to("/admin/series")
+        }
+    }
+}
+
+#[get("/series/<id>")]
+pub async fn edit_series_page(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Option<Template> {
+    let series = db::get_series_by_id(pool.inner(), id).await.ok()?;
+    let articles = db::get_series_articles(pool.inner(), id, true).await.unwrap_or_default();
+
+    // 可添加的文章：不在本系列中的其他文章
+    let mut available = db::get_all_articles(pool.inner(), true).await.unwrap_or_default();
+    available.retain(|a| !articles.iter().any(|s| s.id == a.id));
+
+    Some(Template::render("admin/edit_series", context! { series, articles, available }))
+}
+
+#[post("/series/<id>", data = "<form>")]
+pub async fn update_series(_admin: AdminGuard, id: i32, form: Form<SeriesForm>, pool: &State<PgPool>) -> Redirect {
+    if let Err(e) = db::update_series(pool.inner(), id, &form).await {
+        eprintln!("更新系列失败: {:?}", e);
+        return Redirect::to(format!("/admin/series/{}", id));
+    }
+    Redirect::to("/admin/series")
+}
+
+// 删除系列，文章保留
+#[post("/series/<id>/delete")]
+pub async fn delete_series(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
+    let _ = db::delete_series(pool.inner(), id).await;
+    Redirect::to("/admin/series")
+}
+
+//------------------------------------
+// 回收站
+//------------------------------------
+#[get("/trash")]
+pub async fn trash_page(_admin: AdminGuard, pool: &State<PgPool>) -> Template {
+    render_trash(pool.inner(), None).await
+}
+
+async fn render_trash(pool: &PgPool, message: Option<&str>) -> Template {
+    let (articles, tags) = db::get_trash(pool).await.unwrap_or_default();
+    Template::render("admin/trash", context! {
+        articles,
+        tags,
+        retention_days: site::trash_retention_days(),
+        message,
+    })
+}
+
+// 回收站中的文章不能编辑、查看历史版本或生成预览链接，转到回收站提示先恢复
+async fn trashed_notice(pool: &PgPool, id: i32) -> Option<Template> {
+    match db::is_article_trashed(pool, id).await {
+        Ok(true) => Some(render_trash(pool, Some("这篇文章在回收站中，请先恢复后再编辑、恢复历史版本或生成预览链接")).await),
+        _ => None,
+    }
+}
+
+#[post("/trash/articles/<id>/restore")]
+pub async fn restore_article(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
+    if let Err(e) = db::restore_article(pool.inner(), id).await {
+        eprintln!("恢复文章失败: {:?}", e);
+    }
+    Redirect::to("/admin/trash")
+}
+
+#[post("/trash/articles/<id>/purge")]
+pub async fn purge_article(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
+    let _ = db::purge_article(pool.inner(), id).await;
+    Redirect::to("/admin/trash")
+}
+
+#[post("/trash/tags/<id>/restore")]
+pub async fn restore_tag(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
+    let _ = db::restore_tag(pool.inner(), id).await;
+    Redirect::to("/admin/trash")
+}
+
+#[post("/trash/tags/<id>/purge")]
+pub async fn purge_tag(_admin: AdminGuard, id: i32, pool: &State<PgPool>) -> Redirect {
+    let _ = db::purge_tag(pool.inner(), id).await;
+    Redirect::to("/admin/trash")
+}
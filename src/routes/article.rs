@@ -1,29 +1,148 @@
-use rocket::{get, State};
+use rocket::{get, Responder, State};
+use rocket::http::Header;
+use rocket::response::Redirect;
 use rocket_dyn_templates::{Template, context};
 use crate::db;
+use crate::models::Article;
+use crate::site;
+use serde::Serialize;
 use sqlx::PgPool;
 
-#[get("/<id>")]
-pub async fn article(id: i32, pool: &State<PgPool>) -> Template {
-    // 获取文章
-    let article = match db::get_article_by_id(id, &pool).await {
-        Ok(article) => article,
+fn not_found() -> Template {
+    Template::render("error", context! {
+        message: "文章不存在"
+    })
+}
+
+/// 按 id 查找前台可见的文章，草稿和未到时间的定时文章视为不存在
+async fn get_public_article(id: i32, pool: &PgPool) -> Option<Article> {
+    db::get_article_by_id(id, pool).await.ok().filter(|article| article.status.is_public())
+}
+
+#[derive(Responder)]
+pub enum ArticleResponse {
+    Redirect(Redirect),
+    Page(Template),
+    /// 预览页不允许搜索引擎收录
+    Preview(Template, Header<'static>),
+}
+
+/// 旧地址 `/article/<id>`，301 到永久链接
+///
+/// 带有效的 `preview` token 时直接渲染文章，草稿也可以查看
+#[get("/<id>?<preview>")]
+pub async fn article(id: i32, preview: Option<&str>, pool: &State<PgPool>) -> ArticleResponse {
+    if let Some(token) = preview {
+        return match db::get_article_by_preview_token(pool, id, token).await {
+            Ok(Some(article)) => ArticleResponse::Preview(
+                render_article(article, pool, true).await,
+                Header::new("X-Robots-Tag", "noindex, nofollow"),
+            ),
+            Ok(None) => ArticleResponse::Page(Template::render("error", context! {
+                message: "预览链接无效或已过期"
+            })),
+            Err(e) => {
+                eprintln!("查询预览链接失败: {:?}", e);
+                ArticleResponse::Page(not_found())
+            }
+        };
+    }
+
+    match get_public_article(id, pool).await {
+        // 没有任何时间的文章没有带年份的永久链接，就在此渲染
+        Some(article) if article.url == format!("/article/{}", id) => {
+            ArticleResponse::Page(render_article(article, pool, false).await)
+        }
+        Some(article) => ArticleResponse::Redirect(Redirect::moved(article.url)),
+        None => ArticleResponse::Page(not_found()),
+    }
+}
+
+/// 永久链接 `/posts/<year>/<slug>`
+///
+/// 年份不符或使用了改名前的 slug 时 301 到当前地址
+#[get("/<year>/<slug>")]
+pub async fn post(year: i32, slug: &str, pool: &State<PgPool>) -> Result<Template, Redirect> {
+    let article = match db::get_article_by_slug(pool, slug).await {
+        Ok(article) if article.status.is_public() => article,
+        Ok(_) => return Ok(not_found()),
         Err(_) => {
-            return Template::render("error", context! {
-                message: "文章不存在"
-            });
+            let old = db::get_article_id_by_old_slug(pool, slug).await.unwrap_or_default();
+            let article = match old {
+                Some(id) => get_public_article(id, pool).await,
+                None => None,
+            };
+            return match article {
+                Some(article) => Err(Redirect::moved(article.url)),
+                None => Ok(not_found()),
+            };
         }
     };
 
-    // 使用 Markdown 渲染文章内容
-    let parser = pulldown_cmark::Parser::new(&article.content_md);
-    let mut html_content = String::new();
-    pulldown_cmark::html::push_html(&mut html_content, parser);
+    if article.url != format!("/posts/{}/{}", year, slug) {
+        return Err(Redirect::moved(article.url));
+    }
+
+    Ok(render_article(article, pool, false).await)
+}
+
+/// `base.html.tera` 中的 description、Open Graph 和 Twitter 卡片标签
+#[derive(Serialize)]
+struct PageMeta {
+    title: String,
+    description: String,
+    /// 以下均为绝对地址
+    image: String,
+    /// 有封面图时使用大图卡片
+    has_cover: bool,
+    url: String,
+    canonical_url: String,
+    published_time: Option<String>,
+}
+
+impl PageMeta {
+    fn for_article(article: &Article) -> Self {
+        let description = article
+            .seo_description
+            .clone()
+            .unwrap_or_else(|| article.excerpt.clone());
+        let image = article.cover_image.as_deref().unwrap_or("/static/avatar.jpg");
+        let url = site::absolute_url(&article.url);
+        PageMeta {
+            title: article.title.clone(),
+            description,
+            image: site::absolute_url(image),
+            has_cover: article.cover_image.is_some(),
+            canonical_url: article.canonical_url.as_deref().map(site::absolute_url).unwrap_or_else(|| url.clone()),
+            url,
+            published_time: article.published_at.map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        }
+    }
+}
+
+async fn render_article(article: Article, pool: &PgPool, preview: bool) -> Template {
+    // 引用了本文的文章
+    let backlinks = db::get_backlinks(pool, article.id).await.unwrap_or_default();
+    // 所在系列
+    let series = db::get_article_series_nav(pool, article.id).await.unwrap_or_default();
+    // 上一篇、下一篇和相关文章
+    let (prev, next) = db::get_adjacent_articles(pool, &article).await.unwrap_or_default();
+    let related = db::get_related_articles(pool, &article, 5).await.unwrap_or_default();
+    let meta = PageMeta::for_article(&article);
 
     // 传递给模板的上下文要和模板里变量名一致
     Template::render("article", context! {
         title: &article.title,
         article: &article,
-        article_html: html_content,
+        // 保存时已预渲染
+        article_html: &article.content_html,
+        toc: &article.toc,
+        backlinks,
+        series,
+        prev,
+        next,
+        related,
+        preview,
+        meta,
     })
 }
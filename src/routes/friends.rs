@@ -1,6 +1,7 @@
 use rocket_dyn_templates::{Template, context};
+use crate::site::FRIENDS;
 
 #[get("/")]
 pub fn friends() -> Template {
-    Template::render("friends", context! { title: "友链" })
+    Template::render("friends", context! { title: "友链", friends: FRIENDS })
 }
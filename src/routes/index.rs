@@ -6,7 +6,7 @@ use crate::models::BlogStats;
 
 #[get("/")]
 pub async fn index(pool: &State<PgPool>) -> Template {
-    db::increment_visit(&pool).await.ok();
+    db::increment_visit(pool).await.ok();
 
     let recent_articles = db::get_recent_articles(pool, 5)
         .await
@@ -18,7 +18,7 @@ pub async fn index(pool: &State<PgPool>) -> Template {
 
     let stats = db::get_blog_stats(pool)
         .await
-        .unwrap_or_else(|_| BlogStats {
+        .unwrap_or(BlogStats {
             article_count: 0,
             tag_count: 0,
             days_running: 0,
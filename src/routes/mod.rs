@@ -5,5 +5,6 @@ pub mod about;
 pub mod friends;
 pub mod travel;
 pub mod article;
+pub mod series;
 pub mod admin;
 pub mod middleware;
\ No newline at end of file
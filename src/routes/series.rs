@@ -0,0 +1,22 @@
+use rocket::{get, State};
+use rocket_dyn_templates::{Template, context};
+use crate::db;
+use sqlx::PgPool;
+
+/// 系列页：简介和按顺序排列的已发布文章
+#[get("/<id>")]
+pub async fn series(id: i32, pool: &State<PgPool>) -> Template {
+    let series = match db::get_series_by_id(pool, id).await {
+        Ok(series) => series,
+        Err(_) => return Template::render("error", context! { message: "系列不存在" }),
+    };
+
+    let articles = db::get_series_articles(pool, id, false)
+        .await
+        .unwrap_or_default();
+
+    Template::render("series", context! {
+        series,
+        articles,
+    })
+}
@@ -5,7 +5,7 @@ use sqlx::PgPool;
 
 #[get("/")]
 pub async fn tags(pool: &State<PgPool>) -> Template {
-    let tags = db::get_all_tags(&pool).await.unwrap();
+    let tags = db::get_all_tags(pool).await.unwrap();
     // ✅ 用冒号
     Template::render("tags", context! {
         tags: tags
@@ -21,10 +21,9 @@ pub async fn tag_articles(tag_id: i32, pool: &State<PgPool>) -> Template {
     };
 
     // 2️⃣ 获取该标签下的所有文章
-    let mut articles = match db::get_articles_by_tag(pool, tag_id).await {
-        Ok(list) => list,
-        Err(_) => vec![],
-    };
+    let mut articles = db::get_articles_by_tag(pool, tag_id)
+        .await
+        .unwrap_or_default();
 
     // 3️⃣ 为每篇文章填充完整的 tags 列表
     for article in articles.iter_mut() {
@@ -0,0 +1,105 @@
+//! 站点信息
+//!
+//! 友链列表和本站域名，前台页面和 Markdown 外链处理共用；以及站点地址、回收站保留天数等站点配置。
+
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// 友情链接
+#[derive(Debug, Serialize)]
+pub struct Friend {
+    pub category: &'static str,
+    pub name: &'static str,
+    pub url: &'static str,
+    pub description: &'static str,
+}
+
+/// `/friends` 页面展示的友链，文章中指向这些站点的链接保留 follow
+pub const FRIENDS: &[Friend] = &[
+    Friend {
+        category: "技术博客",
+        name: "Example Blog",
+        url: "https://example.com",
+        description: "优质的技术分享平台",
+    },
+    Friend {
+        category: "个人博客",
+        name: "Another Blog",
+        url: "https://another-example.com",
+        description: "有趣的个人生活记录",
+    },
+    Friend {
+        category: "开源项目",
+        name: "GitHub",
+        url: "https://github.com",
+        description: "代码托管与协作平台",
+    },
+    Friend {
+        category: "视频平台",
+        name: "Bilibili",
+        url: "https://bilibili.com",
+        description: "视频分享社区",
+    },
+];
+
+/// 从 URL 中取出主机名（不含端口）
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map(|(_, rest)| rest)?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// 友链站点的域名
+pub fn friend_hosts() -> &'static [&'static str] {
+    static HOSTS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    HOSTS.get_or_init(|| FRIENDS.iter().filter_map(|f| url_host(f.url)).collect())
+}
+
+/// 本站域名，来自环境变量 `SITE_HOST`（多个用逗号分隔），默认 `localhost`
+pub fn site_hosts() -> &'static [&'static str] {
+    static HOSTS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    HOSTS.get_or_init(|| {
+        let value = std::env::var("SITE_HOST").unwrap_or_else(|_| "localhost".to_string());
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(|host| &*Box::leak(host.to_lowercase().into_boxed_str()))
+            .collect()
+    })
+}
+
+/// 本站地址，来自环境变量 `SITE_URL`，如 `https://blog.example.com`；
+/// 默认为 `https://` 加 `SITE_HOST` 中的第一个域名。用于生成分享卡片等需要的绝对地址。
+pub fn site_url() -> &'static str {
+    static URL: OnceLock<String> = OnceLock::new();
+    URL.get_or_init(|| {
+        std::env::var("SITE_URL")
+            .ok()
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| format!("https://{}", site_hosts().first().copied().unwrap_or("localhost")))
+    })
+}
+
+/// 把站内路径转换为绝对地址，已是绝对地址的原样返回
+pub fn absolute_url(path: &str) -> String {
+    if path.contains("://") {
+        path.to_string()
+    } else {
+        format!("{}/{}", site_url(), path.trim_start_matches('/'))
+    }
+}
+
+/// 回收站保留天数，来自环境变量 `TRASH_RETENTION_DAYS`，默认 30 天
+pub fn trash_retention_days() -> i32 {
+    static DAYS: OnceLock<i32> = OnceLock::new();
+    *DAYS.get_or_init(|| {
+        std::env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(30)
+    })
+}
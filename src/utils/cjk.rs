@@ -0,0 +1,32 @@
+//! 中日韩文字判断
+//!
+//! 排版、字数统计和标题相似度共用同一套字符范围。
+
+/// 中日韩文字（不含标点）
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{2E80}'..='\u{2FDF}'     // 部首
+        | '\u{3040}'..='\u{30FF}'   // 平假名、片假名
+        | '\u{3100}'..='\u{312F}'   // 注音
+        | '\u{3200}'..='\u{32FF}'   // 带圈字符
+        | '\u{3400}'..='\u{4DBF}'   // 扩展 A
+        | '\u{4E00}'..='\u{9FFF}'   // 基本汉字
+        | '\u{AC00}'..='\u{D7AF}'   // 韩文音节
+        | '\u{F900}'..='\u{FAFF}'   // 兼容汉字
+        | '\u{20000}'..='\u{2FA1F}') // 扩展 B 及之后
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_cjk_letters_but_not_punctuation() {
+        for c in ['中', 'か', 'カ', '한', '𠀀', '豈', 'ㄅ'] {
+            assert!(is_cjk(c), "{}", c);
+        }
+        for c in ['a', '1', '，', '。', '「', ' '] {
+            assert!(!is_cjk(c), "{}", c);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! 文章历史版本对比
+//!
+//! 按行比较两个版本的 Markdown，只保留改动附近的几行上下文，分成若干段输出。
+
+use serde::Serialize;
+use std::borrow::Cow;
+use similar::{ChangeTag, TextDiff};
+
+/// 改动前后保留的上下文行数
+const CONTEXT_LINES: usize = 3;
+
+/// 一行对比结果
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct DiffLine {
+    /// `equal` / `insert` / `delete`
+    pub kind: &'static str,
+    /// 在旧版本中的行号（从 1 开始），新增的行为 None
+    pub old_line: Option<usize>,
+    /// 在新版本中的行号（从 1 开始），删除的行为 None
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+/// 一段连续的改动及其上下文
+#[derive(Debug, Serialize)]
+pub struct DiffHunk {
+    pub lines: Vec<DiffLine>,
+}
+
+/// 补上末尾换行，避免最后一行仅因缺少换行而被当作改动
+fn with_trailing_newline(text: &str) -> Cow<'_, str> {
+    if text.is_empty() || text.ends_with('\n') {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(format!("{}\n", text))
+    }
+}
+
+/// 按行对比，没有改动时返回空列表
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffHunk> {
+    let (old, new) = (with_trailing_newline(old), with_trailing_newline(new));
+    let diff = TextDiff::from_lines(old.as_ref(), new.as_ref());
+    diff.grouped_ops(CONTEXT_LINES)
+        .iter()
+        .map(|group| DiffHunk {
+            lines: group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    kind: match change.tag() {
+                        ChangeTag::Equal => "equal",
+                        ChangeTag::Insert => "insert",
+                        ChangeTag::Delete => "delete",
+                    },
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                    text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(hunk: &DiffHunk) -> Vec<&'static str> {
+        hunk.lines.iter().map(|line| line.kind).collect()
+    }
+
+    #[test]
+    fn marks_changed_lines() {
+        let hunks = line_diff("第一行\n第二行\n第三行\n", "第一行\n第二行（改）\n第三行\n新增\n");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(kinds(&hunks[0]), ["equal", "delete", "insert", "equal", "insert"]);
+        assert_eq!(
+            hunks[0].lines[2],
+            DiffLine { kind: "insert", old_line: None, new_line: Some(2), text: "第二行（改）".to_string() }
+        );
+    }
+
+    #[test]
+    fn splits_distant_changes_into_hunks() {
+        let old: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let new = old.replace("line 2\n", "line two\n").replace("line 19\n", "line nineteen\n");
+        let hunks = line_diff(&old, &new);
+        assert_eq!(hunks.len(), 2);
+        // 改动前后各保留 3 行
+        assert_eq!(hunks[1].lines.first().and_then(|l| l.old_line), Some(16));
+        assert_eq!(hunks[1].lines.last().and_then(|l| l.old_line), Some(20));
+    }
+
+    #[test]
+    fn identical_text_has_no_hunks() {
+        assert!(line_diff("a\nb\n", "a\nb\n").is_empty());
+        assert!(line_diff("a\nb", "a\nb\n").is_empty());
+    }
+}
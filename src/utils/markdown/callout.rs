@@ -0,0 +1,286 @@
+//! 提示框（callout）
+//!
+//! - GitHub 风格：`> [!NOTE]`、`> [!TIP]`、`> [!IMPORTANT]`、`> [!WARNING]`、`> [!CAUTION]`
+//! - 可折叠：`:::details 标题` …… `:::`
+
+use pulldown_cmark::{BlockQuoteKind, CowStr, Event, Tag, TagEnd};
+use std::borrow::Cow;
+
+use super::escape_html;
+
+fn kind_meta(kind: BlockQuoteKind) -> (&'static str, &'static str) {
+    match kind {
+        BlockQuoteKind::Note => ("note", "ℹ️ 说明"),
+        BlockQuoteKind::Tip => ("tip", "💡 提示"),
+        BlockQuoteKind::Important => ("important", "❗ 重要"),
+        BlockQuoteKind::Warning => ("warning", "⚠️ 警告"),
+        BlockQuoteKind::Caution => ("caution", "🚫 危险"),
+    }
+}
+
+/// `isolate_markers` 认可的标记行以此开头，`render_callouts` 只处理带它的段落，
+/// 这样缩进代码块、列表项中的 `:::` 即使单独成段也不会被当成标记
+const MARKER_TAG: char = '\u{E002}';
+
+enum Marker {
+    /// `:::details 标题`
+    Open(String),
+    /// `:::`
+    Close,
+}
+
+/// 与围栏一样最多缩进 3 个空格，再多是缩进代码块或列表项中的内容，不算标记
+fn parse_marker(line: &str) -> Option<Marker> {
+    let line = line.trim_end();
+    let unindented = line.trim_start_matches(' ');
+    if line.len() - unindented.len() > 3 {
+        return None;
+    }
+    let rest = unindented.strip_prefix(":::")?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Some(Marker::Close);
+    }
+    let title = rest.strip_prefix("details")?;
+    if !title.is_empty() && !title.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let title = title.trim();
+    Some(Marker::Open(if title.is_empty() { "详情".to_string() } else { title.to_string() }))
+}
+
+/// 代码围栏：行首（最多缩进 3 个空格）连续 3 个以上的 `` ` `` 或 `~`，返回字符、长度和其后的内容
+fn fence_run(line: &str) -> Option<(char, usize, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let c = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = trimmed.len() - trimmed.trim_start_matches(c).len();
+    (len >= 3).then(|| (c, len, &trimmed[len..]))
+}
+
+/// 让 `:::` 标记行单独成段并加上 `MARKER_TAG`，便于在事件流中识别；代码块内的内容保持不变
+///
+/// 与 CommonMark 一致，代码块只由同一字符、长度不短于开头的围栏关闭。
+pub fn isolate_markers(md: &str) -> Cow<'_, str> {
+    if !md.contains(":::") {
+        return Cow::Borrowed(md);
+    }
+    // 正文中本来就有的标记字符去掉，避免伪造标记
+    let md = md.replace(MARKER_TAG, "");
+
+    let mut out = String::with_capacity(md.len() + 16);
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in md.split_inclusive('\n') {
+        match fence {
+            Some((open_char, open_len)) => {
+                let closes = fence_run(line)
+                    .is_some_and(|(c, len, rest)| c == open_char && len >= open_len && rest.trim().is_empty());
+                if closes {
+                    fence = None;
+                }
+                out.push_str(line);
+            }
+            None if fence_run(line).is_some() => {
+                fence = fence_run(line).map(|(c, len, _)| (c, len));
+                out.push_str(line);
+            }
+            None if parse_marker(line).is_some() => {
+                let line = line.trim_end();
+                let marker = line.trim_start_matches(' ');
+                out.push('\n');
+                out.push_str(&line[..line.len() - marker.len()]);
+                out.push(MARKER_TAG);
+                out.push_str(marker);
+                out.push_str("\n\n");
+            }
+            None => out.push_str(line),
+        }
+    }
+
+    Cow::Owned(out)
+}
+
+/// 若 `events[start]` 开始的段落只包含一个 `:::` 标记，返回标记和段落结束位置
+fn marker_paragraph(events: &[Event], start: usize) -> Option<(Marker, usize)> {
+    let mut text = String::new();
+    let mut idx = start + 1;
+    loop {
+        match events.get(idx)? {
+            Event::Text(t) => text.push_str(t),
+            Event::End(TagEnd::Paragraph) => break,
+            _ => return None,
+        }
+        idx += 1;
+    }
+    parse_marker(text.strip_prefix(MARKER_TAG)?).map(|marker| (marker, idx))
+}
+
+/// 将提示框相关事件替换为 callout HTML
+pub fn render_callouts(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut out = Vec::with_capacity(events.len());
+    // 已打开的 `:::details` 层数
+    let mut open_details = 0usize;
+    let mut idx = 0;
+
+    while idx < events.len() {
+        match &events[idx] {
+            Event::Start(Tag::BlockQuote(Some(kind))) => {
+                let (class, title) = kind_meta(*kind);
+                out.push(Event::Html(CowStr::from(format!(
+                    "<div class=\"callout callout-{}\">\n<p class=\"callout-title\">{}</p>\n",
+                    class, title
+                ))));
+            }
+            Event::End(TagEnd::BlockQuote(Some(_))) => {
+                out.push(Event::Html(CowStr::Borrowed("</div>\n")));
+            }
+            Event::Start(Tag::Paragraph) => match marker_paragraph(&events, idx) {
+                Some((Marker::Open(title), end)) => {
+                    open_details += 1;
+                    out.push(Event::Html(CowStr::from(format!(
+                        "<details class=\"callout callout-details\">\n<summary class=\"callout-title\">{}</summary>\n",
+                        escape_html(&title)
+                    ))));
+                    idx = end;
+                }
+                Some((Marker::Close, end)) if open_details > 0 => {
+                    open_details -= 1;
+                    out.push(Event::Html(CowStr::Borrowed("</details>\n")));
+                    idx = end;
+                }
+                _ => out.push(events[idx].clone()),
+            },
+            // 没有配对的标记按普通文字输出
+            Event::Text(text) if text.contains(MARKER_TAG) => {
+                out.push(Event::Text(CowStr::from(text.replace(MARKER_TAG, ""))));
+            }
+            other => out.push(other.clone()),
+        }
+        idx += 1;
+    }
+
+    // 未闭合的 details 在文末自动闭合
+    for _ in 0..open_details {
+        out.push(Event::Html(CowStr::Borrowed("</details>\n")));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{html, Options, Parser};
+
+    fn run(md: &str) -> String {
+        let md = isolate_markers(md);
+        let events = Parser::new_ext(&md, Options::ENABLE_GFM).collect();
+        let mut out = String::new();
+        html::push_html(&mut out, render_callouts(events).into_iter());
+        out
+    }
+
+    #[test]
+    fn github_style_callouts() {
+        let html_out = run("> [!WARNING]\n> 小心 **这里**\n");
+        assert!(html_out.contains("<div class=\"callout callout-warning\">"));
+        assert!(html_out.contains("⚠️ 警告"));
+        assert!(html_out.contains("<strong>这里</strong>"));
+        assert!(!html_out.contains("<blockquote"));
+
+        let plain = run("> 普通引用\n");
+        assert!(plain.contains("<blockquote>"));
+    }
+
+    #[test]
+    fn details_block() {
+        let html_out = run(":::details 点击展开\n隐藏的 *内容*\n:::\n后文\n");
+        assert!(html_out.contains("<details class=\"callout callout-details\">\n<summary class=\"callout-title\">点击展开</summary>"));
+        assert!(html_out.contains("<em>内容</em>"));
+        assert!(html_out.contains("</details>\n<p>后文</p>"));
+    }
+
+    #[test]
+    fn nested_and_mixed_content() {
+        let md = "\
+:::details 外层
+> [!TIP]
+> - 列表项
+>
+> ```rust
+> let x = 1;
+> ```
+
+> [!NOTE]
+> > [!CAUTION]
+> > 内层
+:::
+";
+        let html_out = run(md);
+        assert_eq!(html_out.matches("<details").count(), 1);
+        assert_eq!(html_out.matches("</details>").count(), 1);
+        assert!(html_out.contains("callout-tip"));
+        assert!(html_out.contains("<li>列表项</li>"));
+        assert!(html_out.contains("<code class=\"language-rust\">"));
+        assert!(html_out.contains("callout-note"));
+        assert!(html_out.contains("callout-caution"));
+        assert_eq!(html_out.matches("<div class=\"callout").count(), html_out.matches("</div>").count());
+        assert!(html_out.find("callout-caution").unwrap() < html_out.find("</details>").unwrap());
+    }
+
+    #[test]
+    fn markers_in_code_blocks_are_untouched() {
+        let html_out = run("```\n:::details 不是标记\n:::\n```\n");
+        assert!(!html_out.contains("<details"));
+        assert!(html_out.contains(":::details 不是标记"));
+    }
+
+    #[test]
+    fn markers_in_long_fences_are_untouched() {
+        // 较短的围栏不会关闭代码块
+        let html_out = run("````md\n```\n:::details 不是标记\n:::\n```\n````\n");
+        assert!(!html_out.contains("<details"));
+        assert!(html_out.contains(":::details 不是标记"));
+
+        // 不同字符的围栏也不会
+        let html_out = run("~~~\n```\n:::details 不是标记\n~~~~\n:::details 标记\n内容\n:::\n");
+        assert_eq!(html_out.matches("<details").count(), 1);
+    }
+
+    #[test]
+    fn markers_in_indented_code_are_untouched() {
+        let html_out = run("正文\n\n    :::details 不是标记\n    代码\n    :::\n\n后文\n");
+        assert!(!html_out.contains("<details"));
+        assert!(html_out.contains("<pre><code>:::details 不是标记\n代码\n:::\n</code></pre>"), "{}", html_out);
+
+        // 制表符缩进同样是代码块
+        let html_out = run("正文\n\n\t:::details 不是标记\n");
+        assert!(!html_out.contains("<details"));
+    }
+
+    #[test]
+    fn markers_indented_under_list_items_are_untouched() {
+        let html_out = run("- 第一项\n    :::details 不是标记\n    :::\n- 第二项\n");
+        assert!(!html_out.contains("<details"));
+        assert_eq!(html_out.matches("<li>").count(), 2);
+        assert!(html_out.contains("<li>第一项\n:::details 不是标记\n:::</li>"), "{}", html_out);
+
+        let html_out = run("1. 第一项\n\n    :::details 不是标记\n\n2. 第二项\n");
+        assert!(!html_out.contains("<details"));
+        assert_eq!(html_out.matches("<ol>").count(), 1);
+    }
+
+    #[test]
+    fn unbalanced_markers() {
+        let unclosed = run(":::details\n内容\n");
+        assert!(unclosed.contains("<summary class=\"callout-title\">详情</summary>"));
+        assert!(unclosed.trim_end().ends_with("</details>"));
+
+        let stray = run("文字\n:::\n");
+        assert!(stray.contains("<p>:::</p>"));
+        assert!(!stray.contains("details"));
+    }
+}
@@ -0,0 +1,139 @@
+//! 文章摘要
+//!
+//! 正文中有 `<!--more-->` 时取其之前的内容，否则取正文开头若干字。
+//! 只取段落、列表等正文文字，标题、代码块、图片和原始 HTML 不计入。
+//! 截断时按字符计数，中文可在任意位置截断，英文单词不从中间截断；
+//! 段落之间中文直接相连，英文以空格分隔。
+
+use pulldown_cmark::{Event, Tag, TagEnd};
+
+/// 手动截断标记
+const MORE_MARKER: &str = "<!--more-->";
+
+fn is_more_marker(html: &str) -> bool {
+    html.trim().eq_ignore_ascii_case(MORE_MARKER)
+}
+
+/// 不计入摘要的块
+fn is_skipped_block(tag: &Tag) -> bool {
+    matches!(tag,
+        Tag::Heading { .. } | Tag::CodeBlock(_) | Tag::Image { .. } | Tag::FootnoteDefinition(_)
+        | Tag::HtmlBlock | Tag::MetadataBlock(_))
+}
+
+fn is_skipped_block_end(tag: &TagEnd) -> bool {
+    matches!(tag,
+        TagEnd::Heading(_) | TagEnd::CodeBlock | TagEnd::Image | TagEnd::FootnoteDefinition
+        | TagEnd::HtmlBlock | TagEnd::MetadataBlock(_))
+}
+
+/// 追加文字，连续空白合并为一个空格
+fn push_text(out: &mut String, text: &str) {
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !out.is_empty() && !out.ends_with(' ') {
+                out.push(' ');
+            }
+        } else {
+            out.push(c);
+        }
+    }
+}
+
+/// 按字符数截断，不拆开英文单词，截断后加省略号
+fn truncate(text: &str, max_chars: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= max_chars {
+        return text.to_string();
+    }
+
+    let mut end = max_chars;
+    // 截断点落在英文单词中间时退到单词开头
+    if chars[end - 1].is_ascii_alphanumeric() && chars[end].is_ascii_alphanumeric() {
+        let word_start = chars[..end].iter().rposition(|c| !c.is_ascii_alphanumeric()).map_or(0, |i| i + 1);
+        if word_start > 0 {
+            end = word_start;
+        }
+    }
+
+    let cut: String = chars[..end].iter().collect();
+    let cut = cut.trim_end_matches(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != ')') || "，、；：".contains(c));
+    format!("{}…", cut)
+}
+
+/// 从事件流生成摘要，`max_chars` 为 0 时返回空字符串
+pub fn extract_excerpt(events: &[Event], max_chars: usize) -> String {
+    if max_chars == 0 {
+        return String::new();
+    }
+
+    let mut text = String::new();
+    let mut skip_depth = 0usize;
+    let mut found_marker = false;
+
+    for event in events {
+        match event {
+            Event::Html(html) | Event::InlineHtml(html) if is_more_marker(html) => {
+                found_marker = true;
+                break;
+            }
+            Event::Start(tag) if is_skipped_block(tag) => skip_depth += 1,
+            Event::End(tag) if is_skipped_block_end(tag) => skip_depth = skip_depth.saturating_sub(1),
+            _ if skip_depth > 0 => {}
+            Event::Text(t) | Event::Code(t) | Event::InlineMath(t) => {
+                // 短代码占位符等私用区字符不计入
+                let t: String = t.chars().filter(|c| !('\u{E000}'..='\u{F8FF}').contains(c)).collect();
+                push_text(&mut text, &t);
+            }
+            Event::SoftBreak | Event::HardBreak => push_text(&mut text, " "),
+            // 块之间：中文直接相连，英文加空格
+            Event::End(TagEnd::Paragraph | TagEnd::Item | TagEnd::TableCell)
+                if text.chars().last().is_some_and(|c| c.is_ascii() && !c.is_whitespace()) =>
+            {
+                text.push(' ');
+            }
+            _ => {}
+        }
+    }
+
+    let text = text.trim();
+    if found_marker {
+        text.to_string()
+    } else {
+        truncate(text, max_chars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Options, Parser};
+
+    fn run(md: &str, max_chars: usize) -> String {
+        let events: Vec<Event> = Parser::new_ext(md, Options::ENABLE_MATH).collect();
+        extract_excerpt(&events, max_chars)
+    }
+
+    #[test]
+    fn takes_leading_plain_text() {
+        let md = "# 标题\n\n第一段 **加粗** 和 `code`。\n\n```\nlet x = 1;\n```\n\n第二段 [链接](/a)。\n";
+        assert_eq!(run(md, 100), "第一段 加粗 和 code。第二段 链接。");
+    }
+
+    #[test]
+    fn cuts_at_more_marker() {
+        let md = "引言部分。\n\n<!--more-->\n\n正文很长很长。\n";
+        assert_eq!(run(md, 3), "引言部分。");
+        assert_eq!(run("前面 <!--more--> 后面", 100), "前面");
+    }
+
+    #[test]
+    fn truncates_by_characters() {
+        assert_eq!(run("一二三四五六七八九十", 4), "一二三四…");
+        // 不拆开英文单词
+        assert_eq!(run("Rust ownership rules", 10), "Rust…");
+        assert_eq!(run("学习 Rust ownership", 8), "学习 Rust…");
+        assert_eq!(run("短文本", 10), "短文本");
+        assert_eq!(run("任意内容", 0), "");
+    }
+}
@@ -0,0 +1,246 @@
+//! 代码块语法高亮
+//!
+//! 拦截 pulldown-cmark 的代码块事件，输出带 `hl-*` class 的 HTML，
+//! 颜色由 `static/style.css` 中的主题控制。
+
+use pulldown_cmark::{CodeBlockKind, Event, Tag, TagEnd, CowStr};
+use syntect::html::{line_tokens_to_classed_spans, ClassStyle};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+use std::sync::OnceLock;
+
+use super::escape_html;
+
+/// 高亮 class 前缀，避免与站点样式冲突
+const CLASS_PREFIX: &str = "hl-";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// 代码块围栏信息，如 ```` ```rust {3-5} ````
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FenceInfo {
+    pub lang: Option<String>,
+    /// 需要高亮的行号（从 1 开始，闭区间）
+    pub highlighted: Vec<(usize, usize)>,
+}
+
+impl FenceInfo {
+    pub fn parse(info: &str) -> FenceInfo {
+        let (lang_part, attr_part) = match info.find('{') {
+            Some(pos) => (&info[..pos], Some(&info[pos + 1..])),
+            None => (info, None),
+        };
+
+        let lang = lang_part
+            .split_whitespace()
+            .next()
+            .map(|s| s.to_lowercase());
+
+        let mut highlighted = Vec::new();
+        if let Some(attrs) = attr_part {
+            let attrs = attrs.split('}').next().unwrap_or("");
+            for range in attrs.split(',') {
+                let range = range.trim();
+                let parsed = match range.split_once('-') {
+                    Some((start, end)) => start.trim().parse().ok().zip(end.trim().parse().ok()),
+                    None => range.parse().ok().map(|n| (n, n)),
+                };
+                if let Some((start, end)) = parsed {
+                    if start > 0 && start <= end {
+                        highlighted.push((start, end));
+                    }
+                }
+            }
+        }
+
+        FenceInfo { lang, highlighted }
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted.iter().any(|&(s, e)| line >= s && line <= e)
+    }
+}
+
+fn scope_classes(scope_str: &str) -> String {
+    scope_str
+        .split('.')
+        .map(|atom| format!("{}{}", CLASS_PREFIX, atom))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 逐行高亮，每行包在 `<span class="line">` 内，跨行的 scope 会在下一行重新打开
+fn highlight_lines(code: &str, syntax: &SyntaxReference, info: &FenceInfo) -> String {
+    let ss = syntax_set();
+    let style = ClassStyle::SpacedPrefixed { prefix: CLASS_PREFIX };
+    let mut parse_state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut out = String::with_capacity(code.len() * 2);
+
+    for (idx, line) in LinesWithEndings::from(code).enumerate() {
+        let line_no = idx + 1;
+        if info.is_highlighted(line_no) {
+            out.push_str("<span class=\"line highlighted\">");
+        } else {
+            out.push_str("<span class=\"line\">");
+        }
+
+        // 重新打开上一行未闭合的 scope
+        for scope in stack.as_slice() {
+            out.push_str("<span class=\"");
+            out.push_str(&scope_classes(&scope.build_string()));
+            out.push_str("\">");
+        }
+
+        let rendered = parse_state
+            .parse_line(line, ss)
+            .ok()
+            .and_then(|ops| line_tokens_to_classed_spans(line, &ops, style, &mut stack).ok());
+
+        match rendered {
+            Some((mut html, _)) => {
+                if let Some(pos) = html.rfind('\n') {
+                    html.remove(pos);
+                }
+                out.push_str(&html);
+            }
+            None => out.push_str(&escape_html(line.trim_end_matches('\n'))),
+        }
+
+        for _ in 0..stack.len() {
+            out.push_str("</span>");
+        }
+        out.push_str("</span>");
+    }
+
+    out
+}
+
+/// 无法识别语言时仅做转义，但仍按行包裹以支持行号和行高亮
+fn plain_lines(code: &str, info: &FenceInfo) -> String {
+    let mut out = String::with_capacity(code.len() + 32);
+    for (idx, line) in code.lines().enumerate() {
+        if info.is_highlighted(idx + 1) {
+            out.push_str("<span class=\"line highlighted\">");
+        } else {
+            out.push_str("<span class=\"line\">");
+        }
+        out.push_str(&escape_html(line));
+        out.push_str("</span>");
+    }
+    out
+}
+
+/// 渲染单个代码块
+pub fn render_code_block(code: &str, info: &FenceInfo, line_numbers: bool) -> String {
+    let syntax = info
+        .lang
+        .as_deref()
+        .and_then(|lang| syntax_set().find_syntax_by_token(lang));
+
+    let body = match syntax {
+        Some(syntax) => highlight_lines(code, syntax, info),
+        None => plain_lines(code, info),
+    };
+
+    let mut pre_class = String::from("code-block");
+    if line_numbers {
+        pre_class.push_str(" line-numbers");
+    }
+
+    let mut html = String::new();
+    match &info.lang {
+        Some(lang) => {
+            let lang_escaped = escape_html(lang);
+            html.push_str(&format!(
+                "<pre class=\"{}\" data-lang=\"{}\"><code class=\"language-{}\">",
+                pre_class, lang_escaped, lang_escaped
+            ));
+        }
+        None => html.push_str(&format!("<pre class=\"{}\"><code>", pre_class)),
+    }
+    html.push_str(&body);
+    html.push_str("</code></pre>\n");
+    html
+}
+
+/// 将事件流中的代码块替换为高亮后的 HTML
+pub fn highlight_code_blocks<'a>(events: Vec<Event<'a>>, line_numbers: bool) -> Vec<Event<'a>> {
+    let mut out = Vec::with_capacity(events.len());
+    let mut current: Option<(FenceInfo, String)> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => FenceInfo::parse(&info),
+                    CodeBlockKind::Indented => FenceInfo::default(),
+                };
+                current = Some((info, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((info, code)) = current.take() {
+                    let html = render_code_block(&code, &info, line_numbers);
+                    out.push(Event::Html(CowStr::from(html)));
+                }
+            }
+            Event::Text(text) if current.is_some() => {
+                if let Some((_, code)) = current.as_mut() {
+                    code.push_str(&text);
+                }
+            }
+            other => out.push(other),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fence_info() {
+        assert_eq!(FenceInfo::parse(""), FenceInfo::default());
+        assert_eq!(
+            FenceInfo::parse("Rust {3-5}"),
+            FenceInfo { lang: Some("rust".into()), highlighted: vec![(3, 5)] }
+        );
+        assert_eq!(
+            FenceInfo::parse("py{1, 4-6}"),
+            FenceInfo { lang: Some("py".into()), highlighted: vec![(1, 1), (4, 6)] }
+        );
+        assert_eq!(FenceInfo::parse("{0,5-2,x}").highlighted, vec![]);
+    }
+
+    #[test]
+    fn highlights_known_language() {
+        let info = FenceInfo::parse("rust");
+        let html = render_code_block("fn main() {}\n", &info, false);
+        assert!(html.starts_with("<pre class=\"code-block\" data-lang=\"rust\"><code class=\"language-rust\">"));
+        assert!(html.contains("hl-storage hl-type"));
+        assert!(html.contains("hl-entity hl-name hl-function"));
+    }
+
+    #[test]
+    fn multi_line_scopes_are_balanced_per_line() {
+        let info = FenceInfo::parse("rust {2}");
+        let html = render_code_block("/* a\nb */\nlet x = 1;\n", &info, true);
+        assert_eq!(html.matches("<span class=\"line").count(), 3);
+        assert_eq!(html.matches("<span").count(), html.matches("</span>").count());
+        assert!(html.contains("line-numbers"));
+        assert_eq!(html.matches("line highlighted").count(), 1);
+    }
+
+    #[test]
+    fn unknown_language_is_escaped() {
+        let info = FenceInfo::parse("nosuchlang");
+        let html = render_code_block("<script>\n", &info, false);
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("hl-"));
+    }
+}
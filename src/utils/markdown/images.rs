@@ -0,0 +1,243 @@
+//! 图片
+//!
+//! - 所有图片加 `loading="lazy"`
+//! - `/static/` 下的本地图片读取实际尺寸，写入 `width`/`height` 避免布局抖动
+//! - 图片后紧跟一段强调文字时渲染为 `<figure>`，强调文字作为 `<figcaption>`：
+//!
+//! ```markdown
+//! ![架构图](/static/uploads/arch.png)
+//! *图 1：整体架构*
+//! ```
+
+use pulldown_cmark::{html, CowStr, Event, Tag, TagEnd};
+use std::path::{Component, Path, PathBuf};
+
+use super::escape_html;
+
+/// 本地图片的 URL 前缀，对应 `main.rs` 中挂载的 `FileServer`
+const STATIC_PREFIX: &str = "/static/";
+
+/// 将 `/static/...` 映射为 `static_dir` 下的文件路径，拒绝 `..` 等越界路径
+fn local_path(src: &str, static_dir: &Path) -> Option<PathBuf> {
+    let rest = src.strip_prefix(STATIC_PREFIX)?;
+    let rest = rest.split(['?', '#']).next()?;
+    let relative = Path::new(rest);
+    if rest.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    Some(static_dir.join(relative))
+}
+
+/// 读取本地图片尺寸
+fn local_image_size(src: &str, static_dir: Option<&Path>) -> Option<(usize, usize)> {
+    let path = local_path(src, static_dir?)?;
+    let size = imagesize::size(path).ok()?;
+    Some((size.width, size.height))
+}
+
+/// 图片的替代文字（纯文本）
+fn alt_text(events: &[Event]) -> String {
+    let mut alt = String::new();
+    for event in events {
+        match event {
+            Event::Text(text) | Event::Code(text) | Event::InlineMath(text) => alt.push_str(text),
+            Event::SoftBreak | Event::HardBreak => alt.push(' '),
+            _ => {}
+        }
+    }
+    alt
+}
+
+fn render_img(src: &str, title: &str, alt: &str, static_dir: Option<&Path>) -> String {
+    let mut out = format!("<img src=\"{}\" alt=\"{}\"", escape_html(src), escape_html(alt));
+    if !title.is_empty() {
+        out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+    }
+    out.push_str(" loading=\"lazy\"");
+    if let Some((width, height)) = local_image_size(src, static_dir) {
+        out.push_str(&format!(" width=\"{}\" height=\"{}\"", width, height));
+    }
+    out.push('>');
+    out
+}
+
+/// 从 `start`（`Start(Image)`）开始找到配对的 `End(Image)`
+fn image_end(events: &[Event], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, event) in events.iter().enumerate().skip(start) {
+        match event {
+            Event::Start(Tag::Image { .. }) => depth += 1,
+            Event::End(TagEnd::Image) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_blank(event: &Event) -> bool {
+    match event {
+        Event::SoftBreak | Event::HardBreak => true,
+        Event::Text(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// 段落是否为「图片 + 强调文字」，返回 (图片结束位置, 说明文字范围, 段落结束位置)
+fn match_figure(events: &[Event], para_start: usize) -> Option<(usize, (usize, usize), usize)> {
+    let img_start = para_start + 1;
+    if !matches!(events.get(img_start), Some(Event::Start(Tag::Image { .. }))) {
+        return None;
+    }
+    let img_end = image_end(events, img_start)?;
+
+    let mut idx = img_end + 1;
+    while events.get(idx).is_some_and(is_blank) {
+        idx += 1;
+    }
+    if !matches!(events.get(idx), Some(Event::Start(Tag::Emphasis))) {
+        return None;
+    }
+    let caption_start = idx + 1;
+
+    let mut depth = 1usize;
+    idx += 1;
+    while depth > 0 {
+        match events.get(idx)? {
+            Event::Start(Tag::Emphasis) => depth += 1,
+            Event::End(TagEnd::Emphasis) => depth -= 1,
+            _ => {}
+        }
+        idx += 1;
+    }
+    let caption_end = idx - 1;
+
+    while events.get(idx).is_some_and(is_blank) {
+        idx += 1;
+    }
+    match events.get(idx) {
+        Some(Event::End(TagEnd::Paragraph)) => Some((img_end, (caption_start, caption_end), idx)),
+        _ => None,
+    }
+}
+
+fn image_html(events: &[Event], start: usize, end: usize, static_dir: Option<&Path>) -> String {
+    match &events[start] {
+        Event::Start(Tag::Image { dest_url, title, .. }) => {
+            render_img(dest_url, title, &alt_text(&events[start + 1..end]), static_dir)
+        }
+        _ => String::new(),
+    }
+}
+
+/// 将图片事件替换为带懒加载和尺寸的 `<img>`，并生成 `<figure>`
+///
+/// `static_dir` 为 `None` 时不读取本地图片尺寸。
+pub fn render_images<'a>(events: Vec<Event<'a>>, static_dir: Option<&Path>) -> Vec<Event<'a>> {
+    let mut out = Vec::with_capacity(events.len());
+    let mut idx = 0;
+
+    while idx < events.len() {
+        match &events[idx] {
+            Event::Start(Tag::Paragraph) => match match_figure(&events, idx) {
+                Some((img_end, (caption_start, caption_end), para_end)) => {
+                    let mut caption = String::new();
+                    html::push_html(&mut caption, events[caption_start..caption_end].iter().cloned());
+                    out.push(Event::Html(CowStr::from(format!(
+                        "<figure>\n{}\n<figcaption>{}</figcaption>\n</figure>\n",
+                        image_html(&events, idx + 1, img_end, static_dir),
+                        caption
+                    ))));
+                    idx = para_end;
+                }
+                None => out.push(events[idx].clone()),
+            },
+            Event::Start(Tag::Image { .. }) => match image_end(&events, idx) {
+                Some(end) => {
+                    out.push(Event::InlineHtml(CowStr::from(image_html(&events, idx, end, static_dir))));
+                    idx = end;
+                }
+                None => out.push(events[idx].clone()),
+            },
+            other => out.push(other.clone()),
+        }
+        idx += 1;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Options, Parser};
+    use std::fs;
+
+    fn run(md: &str, static_dir: Option<&Path>) -> String {
+        let events = Parser::new_ext(md, Options::empty()).collect();
+        let mut out = String::new();
+        html::push_html(&mut out, render_images(events, static_dir).into_iter());
+        out
+    }
+
+    /// 写一个只有文件头的 PNG，足够读出尺寸
+    fn fake_png(dir: &Path, name: &str, width: u32, height: u32) {
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13];
+        bytes.extend_from_slice(b"IHDR");
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(name), bytes).unwrap();
+    }
+
+    #[test]
+    fn lazy_loading_and_alt() {
+        let out = run(r#"看图 ![一只 *猫*](https://example.com/cat.jpg "标题") 结束"#, None);
+        assert_eq!(
+            out,
+            "<p>看图 <img src=\"https://example.com/cat.jpg\" alt=\"一只 猫\" title=\"标题\" loading=\"lazy\"> 结束</p>\n"
+        );
+    }
+
+    #[test]
+    fn local_images_get_dimensions() {
+        let dir = std::env::temp_dir().join(format!("blog-images-{}", std::process::id()));
+        fake_png(&dir.join("uploads"), "a.png", 640, 480);
+
+        let out = run("![a](/static/uploads/a.png?v=2)", Some(&dir));
+        assert!(out.contains(r#"loading="lazy" width="640" height="480">"#));
+
+        // 不存在、越界或非本地的图片不加尺寸
+        assert!(!run("![a](/static/uploads/missing.png)", Some(&dir)).contains("width"));
+        assert!(!run("![a](/static/../uploads/a.png)", Some(&dir)).contains("width"));
+        assert!(!run("![a](/uploads/a.png)", Some(&dir)).contains("width"));
+        assert!(!run("![a](/static/uploads/a.png)", None).contains("width"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn image_with_emphasis_becomes_figure() {
+        let out = run("![架构](/a.png)\n*图 1：整体 **架构***\n", None);
+        assert_eq!(
+            out,
+            "<figure>\n<img src=\"/a.png\" alt=\"架构\" loading=\"lazy\">\n<figcaption>图 1：整体 <strong>架构</strong></figcaption>\n</figure>\n"
+        );
+
+        // 同一行、空格分隔也可以
+        assert!(run("![a](/a.png) *说明*", None).contains("<figcaption>说明</figcaption>"));
+    }
+
+    #[test]
+    fn other_paragraphs_are_not_figures() {
+        assert!(!run("![a](/a.png)\n*说明* 后面还有文字\n", None).contains("<figure>"));
+        assert!(!run("前面有文字 ![a](/a.png) *说明*\n", None).contains("<figure>"));
+        assert!(!run("![a](/a.png)\n\n*另起一段*\n", None).contains("<figure>"));
+        assert!(!run("![a](/a.png)\n", None).contains("<figure>"));
+    }
+}
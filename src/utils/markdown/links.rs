@@ -0,0 +1,258 @@
+//! 外链处理
+//!
+//! 指向本站以外的 http(s) 链接在新标签页打开，并加上 `rel="noopener nofollow"`；
+//! 友链站点（`site::FRIENDS`）只加 `noopener`，保留 follow。
+//! 正文中直接写的 `<a>` 不经过事件流，在 HTML 清洗之后由 [`mark_external_anchors`] 处理。
+
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+
+use super::escape_html;
+
+/// 外链判定规则
+#[derive(Debug, Clone, Copy)]
+pub struct LinkPolicy {
+    /// 本站域名
+    pub site_hosts: &'static [&'static str],
+    /// 保留 follow 的域名（含子域名）
+    pub follow_hosts: &'static [&'static str],
+    /// 是否加 `external-link` 类名以显示外链图标
+    pub icon: bool,
+}
+
+enum LinkKind {
+    Internal,
+    Friend,
+    External,
+}
+
+/// 取出 http(s) 或 `//` 开头链接的主机名，其余（相对路径、锚点、mailto 等）返回 None
+fn link_host(url: &str) -> Option<String> {
+    let lower = url.trim().to_ascii_lowercase();
+    let rest = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+        .or_else(|| lower.strip_prefix("//"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.trim_end_matches('.').to_string())
+}
+
+/// `host` 是否为 `domain` 本身或其子域名
+fn matches_domain(host: &str, domain: &str) -> bool {
+    let domain = domain.to_ascii_lowercase();
+    host == domain || host.strip_suffix(&domain).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+impl LinkKind {
+    fn rel(&self) -> Option<&'static str> {
+        match self {
+            LinkKind::Internal => None,
+            LinkKind::Friend => Some("noopener"),
+            LinkKind::External => Some("noopener nofollow"),
+        }
+    }
+}
+
+impl LinkPolicy {
+    fn classify(&self, url: &str) -> LinkKind {
+        let Some(host) = link_host(url) else {
+            return LinkKind::Internal;
+        };
+        let site = |domain: &&str| {
+            let domain = domain.to_ascii_lowercase();
+            host == domain || host.strip_prefix("www.") == Some(domain.as_str())
+        };
+        if self.site_hosts.iter().any(site) {
+            LinkKind::Internal
+        } else if self.follow_hosts.iter().any(|d| matches_domain(&host, d)) {
+            LinkKind::Friend
+        } else {
+            LinkKind::External
+        }
+    }
+}
+
+fn open_tag(url: &str, title: &str, rel: &str, icon: bool) -> String {
+    let mut out = format!("<a href=\"{}\"", escape_html(url));
+    if !title.is_empty() {
+        out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+    }
+    if icon {
+        out.push_str(" class=\"external-link\"");
+    }
+    out.push_str(&format!(" target=\"_blank\" rel=\"{}\">", rel));
+    out
+}
+
+/// 为外链加上 `target` 和 `rel`
+pub fn mark_external_links<'a>(events: Vec<Event<'a>>, policy: &LinkPolicy) -> Vec<Event<'a>> {
+    let mut out = Vec::with_capacity(events.len());
+    // 每层链接是否已替换为 HTML，决定对应的 End 如何输出
+    let mut stack: Vec<bool> = Vec::new();
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+                let rel = match link_type {
+                    LinkType::Email | LinkType::WikiLink { .. } => None,
+                    _ => policy.classify(&dest_url).rel(),
+                };
+                match rel {
+                    Some(rel) => {
+                        out.push(Event::InlineHtml(CowStr::from(open_tag(&dest_url, &title, rel, policy.icon))));
+                        stack.push(true);
+                    }
+                    None => {
+                        out.push(Event::Start(Tag::Link { link_type, dest_url, title, id }));
+                        stack.push(false);
+                    }
+                }
+            }
+            Event::End(TagEnd::Link) => match stack.pop() {
+                Some(true) => out.push(Event::InlineHtml(CowStr::Borrowed("</a>"))),
+                _ => out.push(Event::End(TagEnd::Link)),
+            },
+            other => out.push(other),
+        }
+    }
+
+    out
+}
+
+/// `html` 中从 `<` 开始的标签的结束位置（`>` 的下标），跳过双引号内的内容
+fn tag_end(html: &str) -> Option<usize> {
+    let mut in_quote = false;
+    for (i, c) in html.char_indices() {
+        match c {
+            '"' => in_quote = !in_quote,
+            '>' if !in_quote => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 按外链策略重写一个 `<a ...>` 开始标签，去掉原有的 `target` 和 `rel`
+fn rewrite_anchor(tag: &str, policy: &LinkPolicy) -> Option<String> {
+    let mut attrs: Vec<(&str, Option<&str>)> = Vec::new();
+    let mut rest = tag.strip_prefix("<a")?.strip_suffix('>')?;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let name_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        rest = &rest[name_end..];
+        if let Some(value) = rest.strip_prefix("=\"") {
+            let end = value.find('"')?;
+            attrs.push((name, Some(&value[..end])));
+            rest = &value[end + 1..];
+        } else {
+            attrs.push((name, None));
+        }
+    }
+
+    let href = attrs.iter().find(|(name, _)| *name == "href").and_then(|(_, value)| *value)?;
+    let rel = policy.classify(href).rel()?;
+
+    let mut out = String::from("<a");
+    for (name, value) in attrs.iter().filter(|(name, _)| *name != "target" && *name != "rel") {
+        match value {
+            Some(value) => out.push_str(&format!(" {}=\"{}\"", name, value)),
+            None => out.push_str(&format!(" {}", name)),
+        }
+    }
+    out.push_str(&format!(" target=\"_blank\" rel=\"{}\">", rel));
+    Some(out)
+}
+
+/// 为清洗后 HTML 中的外链 `<a>` 设置 `target` 和 `rel`
+///
+/// 依赖清洗后的规范格式：属性值都用双引号括起，值中的引号已转义。
+pub fn mark_external_anchors(html: &str, policy: &LinkPolicy) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("<a ") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = tag_end(rest) else { break };
+        let tag = &rest[..=end];
+        match rewrite_anchor(tag, policy) {
+            Some(rewritten) => out.push_str(&rewritten),
+            None => out.push_str(tag),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{html, Options, Parser};
+
+    const POLICY: LinkPolicy = LinkPolicy {
+        site_hosts: &["jiao.blog"],
+        follow_hosts: &["github.com"],
+        icon: true,
+    };
+
+    fn run(md: &str, policy: &LinkPolicy) -> String {
+        let events = Parser::new_ext(md, Options::empty()).collect();
+        let mut out = String::new();
+        html::push_html(&mut out, mark_external_links(events, policy).into_iter());
+        out
+    }
+
+    #[test]
+    fn external_links_open_in_new_tab_without_follow() {
+        let out = run(r#"[Rust](https://www.rust-lang.org/learn "官网")"#, &POLICY);
+        assert_eq!(
+            out,
+            "<p><a href=\"https://www.rust-lang.org/learn\" title=\"官网\" class=\"external-link\" target=\"_blank\" rel=\"noopener nofollow\">Rust</a></p>\n"
+        );
+        assert!(run("<https://example.com>", &POLICY).contains(r#"rel="noopener nofollow""#));
+
+        let no_icon = LinkPolicy { icon: false, ..POLICY };
+        assert!(!run("[x](https://example.com)", &no_icon).contains("external-link"));
+    }
+
+    #[test]
+    fn friends_keep_follow() {
+        let out = run("[仓库](https://gist.github.com/x) [主页](http://GitHub.com)", &POLICY);
+        assert_eq!(out.matches(r#"target="_blank" rel="noopener">"#).count(), 2);
+        assert!(!out.contains("nofollow"));
+        assert!(run("[x](https://notgithub.com)", &POLICY).contains("nofollow"));
+    }
+
+    #[test]
+    fn internal_links_are_untouched() {
+        for md in [
+            "[文章](/article/1)",
+            "[锚点](#intro)",
+            "[本站](https://jiao.blog/archive)",
+            "[本站](https://www.jiao.blog)",
+            "[邮件](mailto:a@b.com)",
+            "<a@b.com>",
+        ] {
+            let out = run(md, &POLICY);
+            assert!(!out.contains("target"), "{}", md);
+        }
+    }
+
+    #[test]
+    fn raw_html_anchors_follow_the_policy() {
+        let html = concat!(
+            r#"<p><a href="https://evil.example/?a=1&amp;b=2" target="_blank">x</a> "#,
+            r#"<a href="https://github.com/x" rel="nofollow">y</a> "#,
+            r#"<a href="/posts/2026/a" target="_blank">z</a> <a title="a > b" href="//cdn.example">w</a></p>"#,
+        );
+        let out = mark_external_anchors(html, &POLICY);
+        assert!(out.contains(r#"<a href="https://evil.example/?a=1&amp;b=2" target="_blank" rel="noopener nofollow">x</a>"#));
+        assert!(out.contains(r#"<a href="https://github.com/x" target="_blank" rel="noopener">y</a>"#));
+        assert!(out.contains(r#"<a href="/posts/2026/a" target="_blank">z</a>"#));
+        assert!(out.contains(r#"<a title="a > b" href="//cdn.example" target="_blank" rel="noopener nofollow">w</a>"#));
+    }
+}
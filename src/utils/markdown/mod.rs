@@ -0,0 +1,616 @@
+use pulldown_cmark::{Event, Parser, Options, html};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::site;
+
+mod callout;
+mod excerpt;
+mod highlight;
+mod images;
+mod links;
+mod sanitize;
+mod shortcode;
+mod stats;
+mod toc;
+mod typography;
+mod wikilink;
+
+pub use sanitize::DEFAULT_IFRAME_HOSTS;
+pub use shortcode::referenced_article_ids;
+pub use toc::TocItem;
+pub use wikilink::{referenced_wikilinks, WikiTarget};
+
+/// 渲染管线版本
+///
+/// 修改渲染输出（新增扩展、调整 HTML 结构等）时递增，
+/// 启动时会重新渲染所有版本落后的文章。
+pub const RENDER_VERSION: i32 = 17;
+
+/// Markdown 渲染选项
+///
+/// 所有渲染 `Article::content_md` 的地方都应经过本模块，
+/// 以保证前台、后台看到的效果一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// 表格 `| a | b |`
+    pub tables: bool,
+    /// 脚注 `[^1]`
+    pub footnotes: bool,
+    /// 删除线 `~~text~~`
+    pub strikethrough: bool,
+    /// 任务列表 `- [x] done`
+    pub task_lists: bool,
+    /// 智能标点（引号、破折号、省略号）
+    pub smart_punctuation: bool,
+    /// 标题属性 `# 标题 {#custom-id}`
+    pub heading_ids: bool,
+    /// 数学公式 `$inline$` / `$$display$$`，输出供 KaTeX 渲染的标记
+    pub math: bool,
+    /// 提示框 `> [!NOTE]` 与可折叠的 `:::details`
+    pub callouts: bool,
+    /// 短代码 `{{< bilibili BV1xx >}}`、`{{< article 12 >}}` 等
+    pub shortcodes: bool,
+    /// 中英文混排：中文与英文、数字之间加空格，规范全角标点
+    pub typography: bool,
+    /// 站内维基链接 `[[文章标题]]`、`[[#42]]`
+    pub wikilinks: bool,
+    /// 外链在新标签页打开并加 `rel="noopener nofollow"`
+    pub external_links: bool,
+    /// 外链加 `external-link` 类名，显示外链图标
+    pub external_link_icon: bool,
+    /// 本站域名，指向这些域名的链接不算外链
+    pub site_hosts: &'static [&'static str],
+    /// 保留 follow 的外链域名（含子域名），默认为友链站点
+    pub follow_hosts: &'static [&'static str],
+    /// 图片懒加载、本地图片尺寸和 `<figure>` 说明文字
+    pub images: bool,
+    /// 本地图片（`/static/...`）所在目录，为空则不读取图片尺寸
+    pub static_dir: &'static str,
+    /// 自动生成标题 id 并附加悬停锚点
+    pub heading_anchors: bool,
+    /// 代码块语法高亮
+    pub highlight_code: bool,
+    /// 代码块显示行号
+    pub line_numbers: bool,
+    /// 按白名单清洗输出的 HTML，关闭后原始 HTML 将原样输出
+    pub sanitize: bool,
+    /// 允许嵌入 iframe 的主机，为空则不允许任何 iframe
+    pub iframe_hosts: &'static [&'static str],
+    /// 自动摘要的最大字数，为 0 则不生成；正文中的 `<!--more-->` 优先
+    pub excerpt_length: usize,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            tables: true,
+            footnotes: true,
+            strikethrough: true,
+            task_lists: true,
+            smart_punctuation: false,
+            heading_ids: true,
+            math: true,
+            callouts: true,
+            shortcodes: true,
+            typography: true,
+            wikilinks: true,
+            external_links: true,
+            external_link_icon: true,
+            site_hosts: site::site_hosts(),
+            follow_hosts: site::friend_hosts(),
+            images: true,
+            static_dir: "static",
+            heading_anchors: true,
+            highlight_code: true,
+            line_numbers: true,
+            sanitize: true,
+            iframe_hosts: DEFAULT_IFRAME_HOSTS,
+            excerpt_length: 150,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// 转换为 pulldown-cmark 的解析选项
+    fn parser_options(&self) -> Options {
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_TABLES, self.tables);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        options.set(Options::ENABLE_STRIKETHROUGH, self.strikethrough);
+        options.set(Options::ENABLE_TASKLISTS, self.task_lists);
+        options.set(Options::ENABLE_SMART_PUNCTUATION, self.smart_punctuation);
+        options.set(Options::ENABLE_HEADING_ATTRIBUTES, self.heading_ids);
+        options.set(Options::ENABLE_MATH, self.math);
+        options.set(Options::ENABLE_GFM, self.callouts);
+        options.set(Options::ENABLE_WIKILINKS, self.wikilinks);
+        options
+    }
+}
+
+/// 渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    /// 按标题层级嵌套的目录
+    pub toc: Vec<TocItem>,
+    /// 维基链接指向的文章 id（已去重）
+    pub links: Vec<i32>,
+    /// 找不到目标的维基链接
+    pub broken_links: Vec<String>,
+    /// 自动生成的纯文本摘要
+    pub excerpt: String,
+    /// 字数，中文按字、英文按词计，不含代码块
+    pub word_count: usize,
+    /// 预计阅读分钟数
+    pub reading_minutes: usize,
+}
+
+/// 站内文章引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArticleRef {
+    pub id: i32,
+    pub title: String,
+    /// 永久链接，由调用方按 `models::article_url` 生成
+    pub url: String,
+}
+
+/// 渲染时需要的外部数据
+///
+/// 渲染本身是同步的，需要查数据库的内容（如 `{{< article 12 >}}` 的标题）
+/// 由调用方在渲染前查询好放进来，见 `db::render_article_content`。
+#[derive(Debug, Clone, Default)]
+pub struct RenderContext {
+    pub articles: HashMap<i32, ArticleRef>,
+}
+
+impl RenderContext {
+    /// 按标题查找文章，忽略首尾空白和大小写；重名时取 id 最小的
+    pub fn article_by_title(&self, title: &str) -> Option<&ArticleRef> {
+        let title = title.trim().to_lowercase();
+        self.articles
+            .values()
+            .filter(|article| article.title.trim().to_lowercase() == title)
+            .min_by_key(|article| article.id)
+    }
+}
+
+/// 使用默认选项渲染 Markdown
+pub fn render_markdown(md_input: &str, ctx: &RenderContext) -> RenderedMarkdown {
+    render_markdown_with(md_input, &MarkdownOptions::default(), ctx)
+}
+
+/// 使用指定选项渲染 Markdown
+pub fn render_markdown_with(md_input: &str, opts: &MarkdownOptions, ctx: &RenderContext) -> RenderedMarkdown {
+    let mut md = Cow::Borrowed(md_input);
+    if opts.shortcodes {
+        if let Cow::Owned(stripped) = shortcode::strip_placeholder_chars(&md) {
+            md = Cow::Owned(stripped);
+        }
+    }
+    if opts.callouts {
+        if let Cow::Owned(isolated) = callout::isolate_markers(&md) {
+            md = Cow::Owned(isolated);
+        }
+    }
+
+    let mut events: Vec<Event> = Parser::new_ext(&md, opts.parser_options()).collect();
+
+    if opts.highlight_code {
+        events = highlight::highlight_code_blocks(events, opts.line_numbers);
+    }
+
+    if opts.callouts {
+        events = callout::render_callouts(events);
+    }
+
+    let mut expanded = shortcode::Expanded::default();
+    if opts.shortcodes {
+        events = shortcode::expand_shortcodes(events, ctx, &mut expanded);
+    }
+
+    if opts.typography {
+        events = typography::format_mixed_text(events);
+    }
+
+    let mut links = wikilink::WikiLinks::default();
+    if opts.wikilinks {
+        events = wikilink::resolve_wikilinks(events, ctx, &mut links);
+    }
+
+    let link_policy = links::LinkPolicy {
+        site_hosts: opts.site_hosts,
+        follow_hosts: opts.follow_hosts,
+        icon: opts.external_link_icon,
+    };
+    if opts.external_links {
+        events = links::mark_external_links(events, &link_policy);
+    }
+
+    if opts.images {
+        let static_dir = Some(Path::new(opts.static_dir)).filter(|dir| !dir.as_os_str().is_empty());
+        events = images::render_images(events, static_dir);
+    }
+
+    let excerpt = excerpt::extract_excerpt(&events, opts.excerpt_length);
+    let words = stats::count_words(&events);
+
+    let (events, toc) = toc::build_toc(events, opts.heading_anchors);
+
+    let events = if opts.sanitize { sanitize::prefix_footnote_ids(events) } else { events };
+
+    let mut html_output = String::new();
+    html::push_html(&mut html_output, events.into_iter());
+
+    if opts.sanitize {
+        html_output = sanitize::sanitize_html(&html_output, opts.iframe_hosts);
+        // 正文中直接写的 <a> 同样按外链规则处理
+        if opts.external_links {
+            html_output = links::mark_external_anchors(&html_output, &link_policy);
+        }
+    }
+
+    // 短代码输出是可信 HTML，清洗后再放回
+    html_output = expanded.restore(&html_output);
+
+    // 文章卡片和维基链接一样记入引用关系，被引用的文章改名、删除时重新渲染
+    links.resolved.extend(expanded.articles);
+    links.resolved.sort_unstable();
+    links.resolved.dedup();
+    for raw in expanded.broken_articles {
+        if !links.broken.contains(&raw) {
+            links.broken.push(raw);
+        }
+    }
+
+    RenderedMarkdown {
+        html: html_output,
+        toc,
+        links: links.resolved,
+        broken_links: links.broken,
+        excerpt,
+        word_count: words.total(),
+        reading_minutes: words.reading_minutes(),
+    }
+}
+
+/// 转义 HTML 特殊字符，供各渲染阶段拼接 HTML 时使用
+pub(crate) fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(md: &str) -> RenderedMarkdown {
+        render_markdown(md, &RenderContext::default())
+    }
+
+    fn render_with(md: &str, opts: &MarkdownOptions) -> RenderedMarkdown {
+        render_markdown_with(md, opts, &RenderContext::default())
+    }
+
+    fn only(set: impl FnOnce(&mut MarkdownOptions)) -> MarkdownOptions {
+        let mut opts = MarkdownOptions {
+            tables: false,
+            footnotes: false,
+            strikethrough: false,
+            task_lists: false,
+            smart_punctuation: false,
+            heading_ids: false,
+            math: false,
+            callouts: false,
+            shortcodes: false,
+            typography: false,
+            wikilinks: false,
+            external_links: false,
+            external_link_icon: false,
+            site_hosts: &[],
+            follow_hosts: &[],
+            images: false,
+            static_dir: "",
+            heading_anchors: false,
+            highlight_code: false,
+            line_numbers: false,
+            sanitize: false,
+            iframe_hosts: &[],
+            excerpt_length: 0,
+        };
+        set(&mut opts);
+        opts
+    }
+
+    #[test]
+    fn renders_basic_markdown() {
+        let html = render("# 标题\n\n**粗体** 与 *斜体*").html;
+        assert!(html.contains(r#"<h1 id="标题">标题"#));
+        assert!(html.contains("<strong>粗体</strong>"));
+        assert!(html.contains("<em>斜体</em>"));
+    }
+
+    #[test]
+    fn tables() {
+        let md = "| a | b |\n|---|---|\n| 1 | 2 |\n";
+        assert!(render_with(md, &only(|o| o.tables = true)).html.contains("<table>"));
+        assert!(!render_with(md, &only(|_| {})).html.contains("<table>"));
+    }
+
+    #[test]
+    fn footnotes() {
+        let md = "正文[^1]\n\n[^1]: 脚注内容\n";
+        let html = render_with(md, &only(|o| o.footnotes = true)).html;
+        assert!(html.contains("footnote-reference"));
+        assert!(html.contains("footnote-definition"));
+        assert!(!render_with(md, &only(|_| {})).html.contains("footnote-reference"));
+
+        // 清洗后脚注链接仍能跳转
+        let html = render(md).html;
+        assert!(html.contains(r##"href="#fn-1""##), "{}", html);
+        assert!(html.contains(r#"id="fn-1""#), "{}", html);
+    }
+
+    #[test]
+    fn strikethrough() {
+        let md = "~~删除~~";
+        assert!(render_with(md, &only(|o| o.strikethrough = true)).html.contains("<del>删除</del>"));
+        assert!(!render_with(md, &only(|_| {})).html.contains("<del>"));
+    }
+
+    #[test]
+    fn task_lists() {
+        let md = "- [x] 完成\n- [ ] 未完成\n";
+        let html = render_with(md, &only(|o| o.task_lists = true)).html;
+        assert!(html.contains(r#"<input disabled="" type="checkbox" checked=""/>"#));
+        assert!(html.contains(r#"<input disabled="" type="checkbox"/>"#));
+        assert!(!render_with(md, &only(|_| {})).html.contains("checkbox"));
+    }
+
+    #[test]
+    fn smart_punctuation() {
+        let md = "\"quoted\" -- dash...";
+        let html = render_with(md, &only(|o| o.smart_punctuation = true)).html;
+        assert!(html.contains("“quoted”"));
+        assert!(html.contains("–"));
+        assert!(html.contains("…"));
+        assert!(!render_with(md, &only(|_| {})).html.contains("“"));
+    }
+
+    #[test]
+    fn heading_ids() {
+        let md = "## 安装 {#install}";
+        assert!(render_with(md, &only(|o| o.heading_ids = true)).html.contains(r#"<h2 id="install">安装</h2>"#));
+        assert!(render_with(md, &only(|_| {})).html.contains("{#install}"));
+    }
+
+    #[test]
+    fn highlight_code() {
+        let md = "```rust {2}\nlet a = 1;\nlet b = 2;\n```\n";
+        let html = render_with(md, &only(|o| o.highlight_code = true)).html;
+        assert!(html.contains(r#"<pre class="code-block" data-lang="rust">"#));
+        assert!(html.contains("hl-storage"));
+        assert!(html.contains(r#"<span class="line highlighted">"#));
+
+        let plain = render_with(md, &only(|_| {})).html;
+        assert!(plain.contains(r#"<pre><code class="language-rust">"#));
+    }
+
+    #[test]
+    fn line_numbers() {
+        let md = "```\ncode\n```\n";
+        let html = render_with(md, &only(|o| {
+            o.highlight_code = true;
+            o.line_numbers = true;
+        })).html;
+        assert!(html.contains(r#"<pre class="code-block line-numbers"><code>"#));
+    }
+
+    #[test]
+    fn heading_anchors() {
+        let md = "# 简介\n## 安装 {#install}\n";
+        let rendered = render_with(md, &only(|o| {
+            o.heading_ids = true;
+            o.heading_anchors = true;
+        }));
+        assert!(rendered.html.contains(r##"<h1 id="简介">简介<a class="heading-anchor" href="#简介""##));
+        assert!(rendered.html.contains(r#"<h2 id="install">"#));
+        assert_eq!(rendered.toc[0].children[0].id, "install");
+
+        let plain = render_with(md, &only(|o| o.heading_ids = true));
+        assert!(!plain.html.contains("heading-anchor"));
+        assert_eq!(plain.toc.len(), 1);
+    }
+
+    #[test]
+    fn sanitize() {
+        let md = "<script>alert(1)</script>\n\n<iframe src=\"https://player.bilibili.com/player.html?bvid=BV1\"></iframe>\n";
+        let html = render_with(md, &only(|o| {
+            o.sanitize = true;
+            o.iframe_hosts = DEFAULT_IFRAME_HOSTS;
+        })).html;
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("player.bilibili.com"));
+
+        let no_iframes = render_with(md, &only(|o| o.sanitize = true)).html;
+        assert!(!no_iframes.contains("iframe"));
+
+        assert!(render_with(md, &only(|_| {})).html.contains("<script>"));
+    }
+
+    #[test]
+    fn math() {
+        let md = "行内 $a_1 * b_2 + c_*$ 公式\n\n$$\n\\sum_{i=1}^{n} x_i^2\n$$\n";
+        let html = render_with(md, &only(|o| o.math = true)).html;
+        assert!(html.contains(r#"<span class="math math-inline">a_1 * b_2 + c_*</span>"#));
+        assert!(html.contains(r#"<span class="math math-display">"#));
+        assert!(html.contains(r"\sum_{i=1}^{n} x_i^2"));
+        assert!(!html.contains("<em>"));
+
+        let plain = render_with(md, &only(|_| {})).html;
+        assert!(!plain.contains("math-inline"));
+    }
+
+    #[test]
+    fn math_survives_sanitize() {
+        let html = render_with("$x<y$", &only(|o| {
+            o.math = true;
+            o.sanitize = true;
+        })).html;
+        assert!(html.contains(r#"<span class="math math-inline">x&lt;y</span>"#));
+    }
+
+    #[test]
+    fn dollar_amounts_are_not_math() {
+        let html = render_with("价格 $5 和 $10", &only(|o| o.math = true)).html;
+        assert!(!html.contains("math"));
+    }
+
+    #[test]
+    fn callouts() {
+        let md = "> [!NOTE]\n> 注意\n\n:::details 展开\n内容\n:::\n";
+        let html = render_with(md, &only(|o| {
+            o.callouts = true;
+            o.sanitize = true;
+        })).html;
+        assert!(html.contains(r#"<div class="callout callout-note">"#));
+        assert!(html.contains(r#"<details class="callout callout-details">"#));
+        assert!(html.contains(r#"<summary class="callout-title">展开</summary>"#));
+
+        let plain = render_with(md, &only(|_| {})).html;
+        assert!(plain.contains("<blockquote>"));
+        assert!(plain.contains(":::details"));
+    }
+
+    #[test]
+    fn shortcodes() {
+        let mut ctx = RenderContext::default();
+        ctx.articles.insert(7, ArticleRef { id: 7, title: "上一篇".into(), url: "/posts/2025/shang-yi-pian".into() });
+        let md = "{{< gist octocat 6cad326 >}}\n\n见 {{< article 7 >}}，以及 `{{< article 7 >}}`\n";
+        let opts = only(|o| {
+            o.shortcodes = true;
+            o.sanitize = true;
+        });
+        let html = render_markdown_with(md, &opts, &ctx).html;
+        assert!(html.starts_with(r#"<div class="gist-embed"><script src="https://gist.github.com/octocat/6cad326.js">"#));
+        assert!(html.contains(r#"<p>见 <a class="article-card" href="/posts/2025/shang-yi-pian">"#));
+        assert!(html.contains("<code>{{&lt; article 7 &gt;}}</code>"));
+
+        let plain = render_markdown_with(md, &only(|_| {}), &ctx).html;
+        assert!(!plain.contains("article-card"));
+    }
+
+    #[test]
+    fn article_cards_are_recorded_as_links() {
+        let md = "{{< article 7 >}}\n\n{{< article 8 >}}\n\n```\n{{< article 9 >}}\n```\n";
+        let opts = only(|o| o.shortcodes = true);
+
+        let mut ctx = RenderContext::default();
+        ctx.articles.insert(7, ArticleRef { id: 7, title: "旧标题".into(), url: "/posts/2025/jiu".into() });
+        let rendered = render_markdown_with(md, &opts, &ctx);
+        assert!(rendered.html.contains("旧标题"));
+        // 记入引用关系后，文章 7 改名时会重新渲染本文
+        assert_eq!(rendered.links, vec![7]);
+        assert_eq!(rendered.broken_links, vec!["#8"]);
+
+        ctx.articles.insert(7, ArticleRef { id: 7, title: "新标题".into(), url: "/posts/2025/xin".into() });
+        let rerendered = render_markdown_with(md, &opts, &ctx);
+        assert!(rerendered.html.contains("新标题"));
+        assert!(!rerendered.html.contains("旧标题"));
+    }
+
+    #[test]
+    fn shortcode_placeholders_cannot_be_forged() {
+        let html = render("{{< gist octocat 6cad326 >}}\n\n\u{E000}0\u{E001}\n").html;
+        assert_eq!(html.matches("<script").count(), 1);
+        assert!(html.contains("<p>0</p>"));
+    }
+
+    #[test]
+    fn wikilinks() {
+        let mut ctx = RenderContext::default();
+        ctx.articles.insert(3, ArticleRef { id: 3, title: "Rust 入门".into(), url: "/posts/2024/rust-ru-men".into() });
+        let md = "[[Rust 入门]]、[[#3]] 和 [[没有这篇]]";
+
+        let rendered = render_markdown_with(md, &only(|o| {
+            o.wikilinks = true;
+            o.sanitize = true;
+        }), &ctx);
+        assert!(rendered.html.contains(r#"<a class="wikilink" href="/posts/2024/rust-ru-men""#));
+        assert!(rendered.html.contains(">Rust 入门</a>"));
+        assert!(rendered.html.contains(r#"class="wikilink wikilink-broken""#));
+        assert_eq!(rendered.links, vec![3]);
+        assert_eq!(rendered.broken_links, vec!["没有这篇"]);
+
+        let plain = render_markdown_with(md, &only(|_| {}), &ctx);
+        assert!(plain.html.contains("[[Rust 入门]]"));
+        assert!(plain.links.is_empty());
+    }
+
+    #[test]
+    fn typography() {
+        let md = "## 在Rust中\n\n使用`Vec`很方便.";
+        let rendered = render_with(md, &only(|o| {
+            o.typography = true;
+            o.heading_anchors = true;
+        }));
+        assert!(rendered.html.contains(r#"<h2 id="在-rust-中">在 Rust 中"#));
+        assert!(rendered.html.contains("<p>使用 <code>Vec</code> 很方便。</p>"));
+
+        let plain = render_with(md, &only(|_| {})).html;
+        assert!(plain.contains("<p>使用<code>Vec</code>很方便.</p>"));
+    }
+
+    #[test]
+    fn images() {
+        let md = "![图](https://example.com/a.png)\n*说明*\n\n行内 ![b](/b.png)";
+        let html = render_with(md, &only(|o| {
+            o.images = true;
+            o.sanitize = true;
+        })).html;
+        assert!(html.contains("<figure>\n<img src=\"https://example.com/a.png\" alt=\"图\" loading=\"lazy\">"));
+        assert!(html.contains("<figcaption>说明</figcaption>"));
+        assert!(html.contains("<img src=\"/b.png\" alt=\"b\" loading=\"lazy\">"));
+
+        let plain = render_with(md, &only(|_| {})).html;
+        assert!(!plain.contains("loading"));
+        assert!(!plain.contains("<figure>"));
+    }
+
+    #[test]
+    fn external_links() {
+        let md = "[外站](https://example.org) [友链](https://github.com/rust-lang) [站内](/archive)";
+        let html = render_with(md, &only(|o| {
+            o.external_links = true;
+            o.external_link_icon = true;
+            o.follow_hosts = &["github.com"];
+            o.sanitize = true;
+        })).html;
+        assert!(html.contains(r#"<a href="https://example.org" class="external-link" target="_blank" rel="noopener nofollow">"#));
+        assert!(html.contains(r#"<a href="https://github.com/rust-lang" class="external-link" target="_blank" rel="noopener">"#));
+        assert!(html.contains(r#"<a href="/archive">"#));
+
+        // 原始 HTML 链接不能绕过外链规则
+        let raw = render_with(r#"<a href="https://example.org" target="_blank">x</a>"#, &only(|o| {
+            o.external_links = true;
+            o.sanitize = true;
+        })).html;
+        assert!(raw.contains(r#"<a href="https://example.org" target="_blank" rel="noopener nofollow">x</a>"#));
+
+        let plain = render_with(md, &only(|o| o.sanitize = true)).html;
+        assert!(!plain.contains("target"));
+        assert!(!plain.contains("rel="));
+    }
+}
@@ -0,0 +1,181 @@
+//! 渲染结果的 HTML 清洗
+//!
+//! Markdown 允许内嵌原始 HTML，这里用白名单策略去掉脚本、事件属性等，
+//! 只保留渲染管线自身会产生的标签和属性。
+
+use ammonia::{Builder, Url};
+use pulldown_cmark::{CowStr, Event, Tag};
+use std::borrow::Cow;
+
+/// 默认允许嵌入 iframe 的视频站点
+pub const DEFAULT_IFRAME_HOSTS: &[&str] = &[
+    "player.bilibili.com",
+    "www.youtube.com",
+    "www.youtube-nocookie.com",
+    "player.vimeo.com",
+];
+
+/// 脚注 id 的前缀，清洗时只保留带此前缀的 `<div id>`
+const FOOTNOTE_ID_PREFIX: &str = "fn-";
+
+/// 链接允许的 rel 取值
+const ALLOWED_REL: &[&str] = &["noopener", "noreferrer", "nofollow", "ugc"];
+
+fn filter_rel(value: &str) -> Option<String> {
+    let kept: Vec<&str> = value
+        .split_ascii_whitespace()
+        .filter(|token| ALLOWED_REL.contains(token))
+        .collect();
+    (!kept.is_empty()).then(|| kept.join(" "))
+}
+
+/// iframe 的 src 必须是 https 且主机在白名单内
+fn is_allowed_iframe_src(src: &str, hosts: &[&str]) -> bool {
+    let src = if src.starts_with("//") {
+        Cow::Owned(format!("https:{}", src))
+    } else {
+        Cow::Borrowed(src)
+    };
+
+    match Url::parse(&src) {
+        Ok(url) => {
+            url.scheme() == "https"
+                && url.host_str().is_some_and(|host| hosts.contains(&host))
+        }
+        Err(_) => false,
+    }
+}
+
+/// 脚注的 id 和链接加上前缀，与正文中手写的 id 区分开
+pub fn prefix_footnote_ids(events: Vec<Event>) -> Vec<Event> {
+    let prefixed = |label: CowStr| CowStr::from(format!("{}{}", FOOTNOTE_ID_PREFIX, label));
+    events
+        .into_iter()
+        .map(|event| match event {
+            Event::FootnoteReference(label) => Event::FootnoteReference(prefixed(label)),
+            Event::Start(Tag::FootnoteDefinition(label)) => Event::Start(Tag::FootnoteDefinition(prefixed(label))),
+            event => event,
+        })
+        .collect()
+}
+
+fn policy(iframe_hosts: &'static [&'static str]) -> Builder<'static> {
+    let mut builder = Builder::default();
+
+    // rel 由外链处理阶段决定（原始 HTML 中的链接在清洗后处理，见 `links::mark_external_anchors`），这里只过滤取值
+    // id 只留给目录锚点和脚注，任意元素都能带 id 会覆盖页面自身的 id 或 DOM 全局变量
+    builder
+        .link_rel(None)
+        .add_generic_attributes(["class"])
+        .add_tag_attributes("h1", ["id"])
+        .add_tag_attributes("h2", ["id"])
+        .add_tag_attributes("h3", ["id"])
+        .add_tag_attributes("h4", ["id"])
+        .add_tag_attributes("h5", ["id"])
+        .add_tag_attributes("h6", ["id"])
+        .add_tag_attributes("div", ["id"])
+        .add_tags(["input"])
+        .add_tag_attributes("pre", ["data-lang"])
+        .add_tag_attributes("a", ["aria-hidden", "target", "rel"])
+        .add_tag_attributes("img", ["loading"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .attribute_filter(move |element, attribute, value| match (element, attribute) {
+            ("div", "id") if !value.starts_with(FOOTNOTE_ID_PREFIX) => None,
+            ("input", "type") if value != "checkbox" => None,
+            ("a", "target") if value != "_blank" => None,
+            ("a", "rel") => filter_rel(value).map(Into::into),
+            ("img", "loading") if value != "lazy" && value != "eager" => None,
+            ("iframe", "src") if !is_allowed_iframe_src(value, iframe_hosts) => None,
+            _ => Some(value.into()),
+        });
+
+    if !iframe_hosts.is_empty() {
+        builder
+            .add_tags(["iframe"])
+            .add_tag_attributes("iframe", ["src", "width", "height", "title", "frameborder", "allowfullscreen"]);
+    }
+
+    builder
+}
+
+/// 按白名单清洗 HTML
+pub fn sanitize_html(html: &str, iframe_hosts: &'static [&'static str]) -> String {
+    policy(iframe_hosts).clean(html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_scripts_and_event_handlers() {
+        let out = sanitize_html(
+            r#"<p onclick="alert(1)">hi</p><script>alert(1)</script><a href="javascript:alert(1)">x</a>"#,
+            DEFAULT_IFRAME_HOSTS,
+        );
+        assert!(!out.contains("script"));
+        assert!(!out.contains("onclick"));
+        assert!(!out.contains("javascript:"));
+        assert!(out.contains("<p>hi</p>"));
+    }
+
+    #[test]
+    fn keeps_pipeline_markup() {
+        let html = r##"<h2 id="安装">安装<a class="heading-anchor" href="#安装" aria-hidden="true">#</a></h2><pre class="code-block" data-lang="rust"><code class="language-rust"><span class="line hl-keyword">fn</span></code></pre><input disabled="" type="checkbox" checked=""><figure><img src="/static/a.png" alt="a" loading="lazy" width="10" height="20"><figcaption>说明</figcaption></figure>"##;
+        let out = sanitize_html(html, DEFAULT_IFRAME_HOSTS);
+        assert!(out.contains(r#"<h2 id="安装">"#));
+        assert!(out.contains(r#"aria-hidden="true""#));
+        assert!(out.contains(r#"<pre class="code-block" data-lang="rust">"#));
+        assert!(out.contains(r#"<span class="line hl-keyword">"#));
+        assert!(out.contains(r#"type="checkbox""#));
+        assert!(out.contains(r#"<figure><img src="/static/a.png" alt="a" loading="lazy" width="10" height="20"><figcaption>"#));
+    }
+
+    #[test]
+    fn link_rel_and_target_are_filtered() {
+        let out = sanitize_html(
+            r#"<a href="https://x.org" target="_blank" rel="noopener nofollow opener">a</a><a href="/b" target="_top" rel="opener">b</a>"#,
+            DEFAULT_IFRAME_HOSTS,
+        );
+        assert!(out.contains(r#"<a href="https://x.org" target="_blank" rel="noopener nofollow">a</a>"#));
+        assert!(out.contains(r#"<a href="/b">b</a>"#));
+    }
+
+    #[test]
+    fn ids_only_on_headings_and_footnotes() {
+        let out = sanitize_html(
+            r#"<h3 id="a">a</h3><form id="x"></form><img id="y" src="/a.png"><div id="z">z</div><div id="fn-1">1</div>"#,
+            DEFAULT_IFRAME_HOSTS,
+        );
+        assert!(out.contains(r#"<h3 id="a">"#));
+        assert!(out.contains(r#"<div id="fn-1">"#));
+        assert!(!out.contains(r#"id="x""#));
+        assert!(!out.contains(r#"id="y""#));
+        assert!(!out.contains(r#"id="z""#));
+    }
+
+    #[test]
+    fn only_checkbox_inputs() {
+        let out = sanitize_html(r#"<input type="text" value="x">"#, DEFAULT_IFRAME_HOSTS);
+        assert!(!out.contains("text"));
+    }
+
+    #[test]
+    fn iframes_limited_to_whitelisted_hosts() {
+        let ok = sanitize_html(
+            r#"<iframe src="//player.bilibili.com/player.html?bvid=BV1xx" onload="x()" allowfullscreen="true"></iframe>"#,
+            DEFAULT_IFRAME_HOSTS,
+        );
+        assert!(ok.contains(r#"src="//player.bilibili.com/player.html?bvid=BV1xx""#));
+        assert!(!ok.contains("onload"));
+
+        let evil = sanitize_html(r#"<iframe src="https://evil.example/"></iframe>"#, DEFAULT_IFRAME_HOSTS);
+        assert!(!evil.contains("evil.example"));
+
+        let insecure = sanitize_html(r#"<iframe src="http://www.youtube.com/embed/x"></iframe>"#, DEFAULT_IFRAME_HOSTS);
+        assert!(!insecure.contains("youtube"));
+
+        let disabled = sanitize_html(r#"<iframe src="https://www.youtube.com/embed/x"></iframe>"#, &[]);
+        assert!(!disabled.contains("iframe"));
+    }
+}
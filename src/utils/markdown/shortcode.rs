@@ -0,0 +1,412 @@
+//! 短代码（shortcode）
+//!
+//! 语法：`{{< name arg1 key="value" >}}`，由注册表中的 Rust 函数展开为 HTML。
+//! 代码块和行内代码中的短代码保持原样。处理函数输出的 HTML 视为可信内容，
+//! 先以占位符写入，待清洗完成后再替换回去。
+
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::{escape_html, RenderContext};
+
+/// 占位符首尾字符（Unicode 私用区，正常文本不会出现）
+const PLACEHOLDER_START: char = '\u{E000}';
+const PLACEHOLDER_END: char = '\u{E001}';
+
+/// 解析后的短代码
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcode {
+    pub name: String,
+    /// 位置参数
+    pub args: Vec<String>,
+    /// 命名参数 `key="value"`
+    pub params: HashMap<String, String>,
+}
+
+impl Shortcode {
+    fn parse(inner: &str) -> Option<Shortcode> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+        for c in inner.trim().chars() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                c if c.is_whitespace() && !in_quotes => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+
+        let mut tokens = tokens.into_iter();
+        let name = tokens.next()?;
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return None;
+        }
+
+        let mut args = Vec::new();
+        let mut params = HashMap::new();
+        for token in tokens {
+            match token.split_once('=') {
+                Some((key, value)) if !key.is_empty() => {
+                    params.insert(key.to_string(), value.to_string());
+                }
+                _ => args.push(token),
+            }
+        }
+
+        Some(Shortcode { name, args, params })
+    }
+
+    fn arg(&self, idx: usize) -> Option<&str> {
+        self.args.get(idx).map(String::as_str)
+    }
+}
+
+/// 在文本中查找短代码，返回 (起始, 结束, 短代码)
+fn find_shortcodes(text: &str) -> Vec<(usize, usize, Shortcode)> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = text[pos..].find("{{<").map(|i| i + pos) {
+        let inner_start = start + 3;
+        let Some(end) = text[inner_start..].find(">}}").map(|i| i + inner_start) else {
+            break;
+        };
+        match Shortcode::parse(&text[inner_start..end]) {
+            Some(sc) => {
+                found.push((start, end + 3, sc));
+                pos = end + 3;
+            }
+            None => pos = inner_start,
+        }
+    }
+    found
+}
+
+/// 短代码处理函数，参数无效时返回错误信息
+pub type ShortcodeHandler = fn(&Shortcode, &RenderContext) -> Result<String, String>;
+
+/// 短代码注册表
+pub struct ShortcodeRegistry {
+    handlers: HashMap<&'static str, ShortcodeHandler>,
+}
+
+impl ShortcodeRegistry {
+    pub fn new() -> Self {
+        ShortcodeRegistry { handlers: HashMap::new() }
+    }
+
+    pub fn register(&mut self, name: &'static str, handler: ShortcodeHandler) -> &mut Self {
+        self.handlers.insert(name, handler);
+        self
+    }
+
+    /// 内置短代码
+    pub fn builtin() -> &'static ShortcodeRegistry {
+        static REGISTRY: OnceLock<ShortcodeRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(|| {
+            let mut registry = ShortcodeRegistry::new();
+            registry
+                .register("bilibili", bilibili)
+                .register("gist", gist)
+                .register("article", article_card);
+            registry
+        })
+    }
+
+    fn render(&self, sc: &Shortcode, ctx: &RenderContext) -> Option<String> {
+        let handler = self.handlers.get(sc.name.as_str())?;
+        Some(match handler(sc, ctx) {
+            Ok(html) => html,
+            Err(msg) => format!("<span class=\"shortcode-error\">{}</span>", escape_html(&msg)),
+        })
+    }
+}
+
+impl Default for ShortcodeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `{{< bilibili BV1xx411c7mD >}}`，可选 `page=2`
+fn bilibili(sc: &Shortcode, _ctx: &RenderContext) -> Result<String, String> {
+    let bvid = sc.arg(0).ok_or("bilibili 短代码缺少 BV 号")?;
+    let valid = bvid.starts_with("BV")
+        && bvid.len() == 12
+        && bvid.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid {
+        return Err(format!("无效的 BV 号：{}", bvid));
+    }
+    let page: u32 = sc.params.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+
+    Ok(format!(
+        "<div class=\"video-embed\"><iframe src=\"https://player.bilibili.com/player.html?bvid={}&amp;page={}&amp;autoplay=0\" \
+         title=\"bilibili video\" frameborder=\"0\" allowfullscreen=\"true\"></iframe></div>\n",
+        bvid, page
+    ))
+}
+
+/// `{{< gist user 0123abcd >}}`，可选 `file="main.rs"`
+fn gist(sc: &Shortcode, _ctx: &RenderContext) -> Result<String, String> {
+    let (user, id) = sc.arg(0).zip(sc.arg(1)).ok_or("gist 短代码需要用户名和 gist id")?;
+    let valid_user = user.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    let valid_id = id.chars().all(|c| c.is_ascii_hexdigit());
+    if !valid_user || !valid_id {
+        return Err(format!("无效的 gist：{}/{}", user, id));
+    }
+
+    let file = match sc.params.get("file") {
+        Some(f) if f.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c)) => {
+            format!("?file={}", f)
+        }
+        Some(f) => return Err(format!("无效的 gist 文件名：{}", f)),
+        None => String::new(),
+    };
+
+    Ok(format!(
+        "<div class=\"gist-embed\"><script src=\"https://gist.github.com/{}/{}.js{}\"></script>\
+         <noscript><a href=\"https://gist.github.com/{}/{}\">查看 Gist</a></noscript></div>\n",
+        user, id, file, user, id
+    ))
+}
+
+/// `{{< article 12 >}}` 或 `{{< article #12 >}}` 中的文章 id
+fn article_id(sc: &Shortcode) -> Option<i32> {
+    sc.arg(0)?.trim_start_matches('#').parse().ok()
+}
+
+/// `{{< article 12 >}}`，标题来自渲染前查询的 `RenderContext::articles`
+fn article_card(sc: &Shortcode, ctx: &RenderContext) -> Result<String, String> {
+    let id = article_id(sc).ok_or("article 短代码需要文章 id")?;
+    let article = ctx.articles.get(&id).ok_or(format!("文章 #{} 不存在", id))?;
+
+    Ok(format!(
+        "<a class=\"article-card\" href=\"{}\">\
+         <span class=\"article-card-label\">📄 站内文章</span>\
+         <span class=\"article-card-title\">{}</span></a>",
+        escape_html(&article.url),
+        escape_html(&article.title)
+    ))
+}
+
+/// 收集 `{{< article N >}}` 引用的文章 id，用于渲染前查询数据库
+pub fn referenced_article_ids(md: &str) -> Vec<i32> {
+    let mut ids: Vec<i32> = find_shortcodes(md)
+        .into_iter()
+        .filter(|(_, _, sc)| sc.name == "article")
+        .filter_map(|(_, _, sc)| article_id(&sc))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// 去掉输入中的占位符字符，防止伪造占位符
+pub fn strip_placeholder_chars(md: &str) -> Cow<'_, str> {
+    if md.contains([PLACEHOLDER_START, PLACEHOLDER_END]) {
+        Cow::Owned(md.replace([PLACEHOLDER_START, PLACEHOLDER_END], ""))
+    } else {
+        Cow::Borrowed(md)
+    }
+}
+
+/// 短代码展开结果，`html` 在清洗后由 [`Expanded::restore`] 替换回占位符
+#[derive(Default)]
+pub struct Expanded {
+    html: Vec<String>,
+    /// 文章卡片引用的文章 id，与维基链接一起记入引用关系
+    pub articles: Vec<i32>,
+    /// 文章卡片中找不到的文章，以 `#id` 记录，同维基链接的失效链接
+    pub broken_articles: Vec<String>,
+}
+
+impl Expanded {
+    /// 渲染短代码，同时记录文章卡片引用的文章
+    fn render(&mut self, registry: &ShortcodeRegistry, sc: &Shortcode, ctx: &RenderContext) -> Option<String> {
+        let html = registry.render(sc, ctx)?;
+        if let Some(id) = article_id(sc).filter(|_| sc.name == "article") {
+            if ctx.articles.contains_key(&id) {
+                self.articles.push(id);
+            } else {
+                self.broken_articles.push(format!("#{}", id));
+            }
+        }
+        Some(html)
+    }
+
+    fn placeholder(&mut self, html: String) -> String {
+        self.html.push(html);
+        format!("{}{}{}", PLACEHOLDER_START, self.html.len() - 1, PLACEHOLDER_END)
+    }
+
+    /// 将占位符替换为短代码输出
+    pub fn restore(&self, html: &str) -> String {
+        if self.html.is_empty() {
+            return html.to_string();
+        }
+        let mut out = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find(PLACEHOLDER_START) {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + PLACEHOLDER_START.len_utf8()..];
+            let parsed = after.find(PLACEHOLDER_END).and_then(|end| {
+                let idx: usize = after[..end].parse().ok()?;
+                Some((self.html.get(idx)?, end))
+            });
+            match parsed {
+                Some((replacement, end)) => {
+                    out.push_str(replacement);
+                    rest = &after[end + PLACEHOLDER_END.len_utf8()..];
+                }
+                None => rest = after,
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// 展开一段连续文本中的短代码
+fn expand_text<'a>(
+    text: String,
+    registry: &ShortcodeRegistry,
+    ctx: &RenderContext,
+    expanded: &mut Expanded,
+    out: &mut Vec<Event<'a>>,
+) {
+    let mut last = 0;
+    for (start, end, sc) in find_shortcodes(&text) {
+        if let Some(html) = expanded.render(registry, &sc, ctx) {
+            if start > last {
+                out.push(Event::Text(CowStr::from(text[last..start].to_string())));
+            }
+            out.push(Event::InlineHtml(CowStr::from(expanded.placeholder(html))));
+            last = end;
+        }
+    }
+    if last < text.len() {
+        out.push(Event::Text(CowStr::from(text[last..].to_string())));
+    }
+}
+
+/// 展开事件流中的短代码
+///
+/// 单独成段的短代码以块级 HTML 输出，不会被包进 `<p>`。
+pub fn expand_shortcodes<'a>(
+    events: Vec<Event<'a>>,
+    ctx: &RenderContext,
+    expanded: &mut Expanded,
+) -> Vec<Event<'a>> {
+    let registry = ShortcodeRegistry::builtin();
+    let mut out: Vec<Event<'a>> = Vec::with_capacity(events.len());
+    let mut pending: Option<String> = None;
+    let mut in_code_block = false;
+
+    for event in events {
+        match event {
+            Event::Text(text) if !in_code_block => {
+                pending.get_or_insert_with(String::new).push_str(&text);
+                continue;
+            }
+            _ => {}
+        }
+
+        if let Some(text) = pending.take() {
+            let paragraph_start = matches!(out.last(), Some(Event::Start(Tag::Paragraph)));
+            let own_paragraph = paragraph_start && matches!(event, Event::End(TagEnd::Paragraph));
+            let single = find_shortcodes(&text);
+            let is_block = own_paragraph
+                && single.len() == 1
+                && single[0].0 == 0
+                && single[0].1 == text.len();
+
+            match (is_block, single.first()) {
+                (true, Some((_, _, sc))) => match expanded.render(registry, sc, ctx) {
+                    Some(html) => {
+                        out.pop();
+                        out.push(Event::Html(CowStr::from(expanded.placeholder(html))));
+                        continue;
+                    }
+                    None => expand_text(text, registry, ctx, expanded, &mut out),
+                },
+                _ => expand_text(text, registry, ctx, expanded, &mut out),
+            }
+        }
+
+        match &event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            _ => {}
+        }
+        out.push(event);
+    }
+
+    if let Some(text) = pending.take() {
+        expand_text(text, registry, ctx, expanded, &mut out);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::markdown::ArticleRef;
+
+    #[test]
+    fn parses_arguments() {
+        let sc = Shortcode::parse(r#" gist octocat 6cad326 file="hello world.rs" "#).unwrap();
+        assert_eq!(sc.name, "gist");
+        assert_eq!(sc.args, vec!["octocat", "6cad326"]);
+        assert_eq!(sc.params["file"], "hello world.rs");
+        assert!(Shortcode::parse("  ").is_none());
+        assert!(Shortcode::parse("<script>").is_none());
+    }
+
+    #[test]
+    fn finds_multiple_shortcodes() {
+        let found = find_shortcodes("a {{< x 1 >}} b {{< y >}} {{< broken");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].2.name, "y");
+    }
+
+    #[test]
+    fn collects_article_references() {
+        let md = "{{< article 3 >}} {{< article #1 >}} {{< article 3 >}} {{< bilibili BV1xx411c7mD >}}";
+        assert_eq!(referenced_article_ids(md), vec![1, 3]);
+    }
+
+    #[test]
+    fn builtin_handlers() {
+        let mut ctx = RenderContext::default();
+        ctx.articles.insert(12, ArticleRef { id: 12, title: "<Rust> 入门".into(), url: "/posts/2025/rust-ru-men".into() });
+
+        let render = |s: &str| {
+            let sc = Shortcode::parse(s).unwrap();
+            ShortcodeRegistry::builtin().render(&sc, &ctx).unwrap()
+        };
+
+        assert!(render("bilibili BV1xx411c7mD page=2").contains("bvid=BV1xx411c7mD&amp;page=2"));
+        assert!(render("bilibili \"onload=x\"").contains("shortcode-error"));
+        assert!(render("gist octocat 6cad326").contains("https://gist.github.com/octocat/6cad326.js"));
+        assert!(render("gist octocat 6cad326 file=\"a\\\"b\"").contains("shortcode-error"));
+        assert!(render("article 12").contains("&lt;Rust&gt; 入门"));
+        assert!(render("article 13").contains("文章 #13 不存在"));
+    }
+
+    #[test]
+    fn placeholders_round_trip() {
+        let mut expanded = Expanded::default();
+        let p = expanded.placeholder("<b>x</b>".into());
+        let html = format!("<p>{}</p>\u{E000}9\u{E001}", p);
+        assert_eq!(expanded.restore(&html), "<p><b>x</b></p>9\u{E001}");
+    }
+}
@@ -0,0 +1,126 @@
+//! 字数统计与阅读时间
+//!
+//! 中日韩文字每个字计一字，其他文字按空白和标点分隔的单词计数；
+//! 代码块、公式、图片和原始 HTML 不计入。
+//! 阅读速度按中文每分钟 300 字、英文每分钟 200 词估算。
+
+use pulldown_cmark::{Event, Tag, TagEnd};
+
+use crate::utils::cjk::is_cjk;
+
+/// 中文每分钟阅读字数
+const CJK_CHARS_PER_MINUTE: usize = 300;
+/// 英文每分钟阅读词数
+const LATIN_WORDS_PER_MINUTE: usize = 200;
+
+/// 字数统计结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WordCount {
+    /// 中日韩文字数
+    pub cjk: usize,
+    /// 其他文字的单词数
+    pub latin: usize,
+}
+
+impl WordCount {
+    /// 总字数
+    pub fn total(self) -> usize {
+        self.cjk + self.latin
+    }
+
+    /// 预计阅读分钟数，向上取整；有内容时至少 1 分钟
+    pub fn reading_minutes(self) -> usize {
+        // 两种速度交叉相乘换成同一整数单位再累加，避免浮点
+        let units = self.cjk * LATIN_WORDS_PER_MINUTE + self.latin * CJK_CHARS_PER_MINUTE;
+        units.div_ceil(CJK_CHARS_PER_MINUTE * LATIN_WORDS_PER_MINUTE)
+    }
+}
+
+/// 不计入字数的块
+fn is_skipped_block(tag: &Tag) -> bool {
+    matches!(tag,
+        Tag::CodeBlock(_) | Tag::Image { .. } | Tag::HtmlBlock | Tag::MetadataBlock(_))
+}
+
+fn is_skipped_block_end(tag: &TagEnd) -> bool {
+    matches!(tag,
+        TagEnd::CodeBlock | TagEnd::Image | TagEnd::HtmlBlock | TagEnd::MetadataBlock(_))
+}
+
+/// 不打断单词的行内标签，如 `**Rust**acean` 仍算一个词
+fn is_inline_format(event: &Event) -> bool {
+    matches!(event,
+        Event::Start(Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. })
+        | Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link))
+}
+
+/// 统计事件流中的字数
+pub fn count_words(events: &[Event]) -> WordCount {
+    let mut count = WordCount::default();
+    let mut skip_depth = 0usize;
+    let mut in_word = false;
+
+    for event in events {
+        match event {
+            Event::Start(tag) if is_skipped_block(tag) => skip_depth += 1,
+            Event::End(tag) if is_skipped_block_end(tag) => skip_depth = skip_depth.saturating_sub(1),
+            _ if skip_depth > 0 => {}
+            Event::Text(text) | Event::Code(text) => {
+                for c in text.chars() {
+                    if is_cjk(c) {
+                        count.cjk += 1;
+                        in_word = false;
+                    } else if c.is_alphanumeric() {
+                        if !in_word {
+                            count.latin += 1;
+                        }
+                        in_word = true;
+                    } else if !matches!(c, '\'' | '’' | '-' | '_') || !in_word {
+                        // 单词内的撇号、连字符不拆词；短代码占位符等也在此作为分隔
+                        in_word = false;
+                    }
+                }
+            }
+            event if is_inline_format(event) => {}
+            _ => in_word = false,
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Options, Parser};
+
+    fn run(md: &str) -> WordCount {
+        let events: Vec<Event> = Parser::new_ext(md, Options::ENABLE_MATH).collect();
+        count_words(&events)
+    }
+
+    #[test]
+    fn counts_cjk_characters_and_latin_words() {
+        assert_eq!(run("你好，世界！"), WordCount { cjk: 4, latin: 0 });
+        assert_eq!(run("Hello, world! It's a well-known fact."), WordCount { cjk: 0, latin: 6 });
+        assert_eq!(run("学习Rust的所有权"), WordCount { cjk: 6, latin: 1 });
+        assert_eq!(run("**Rust**acean 和 `cargo`"), WordCount { cjk: 1, latin: 2 });
+        assert_eq!(run("# 标题\n\n第一行\n第二行"), WordCount { cjk: 8, latin: 0 });
+    }
+
+    #[test]
+    fn skips_code_blocks_math_and_images() {
+        let md = "正文\n\n```rust\nfn main() { println!(\"你好\"); }\n```\n\n$$x^2$$ ![图片说明](/a.png)\n";
+        assert_eq!(run(md), WordCount { cjk: 2, latin: 0 });
+    }
+
+    #[test]
+    fn estimates_reading_minutes() {
+        assert_eq!(WordCount::default().reading_minutes(), 0);
+        assert_eq!(WordCount { cjk: 1, latin: 0 }.reading_minutes(), 1);
+        assert_eq!(WordCount { cjk: 300, latin: 0 }.reading_minutes(), 1);
+        assert_eq!(WordCount { cjk: 301, latin: 0 }.reading_minutes(), 2);
+        assert_eq!(WordCount { cjk: 300, latin: 200 }.reading_minutes(), 2);
+        assert_eq!(WordCount { cjk: 0, latin: 1000 }.reading_minutes(), 5);
+    }
+}
@@ -0,0 +1,216 @@
+//! 标题锚点与目录（TOC）
+//!
+//! 为每个标题生成稳定的 id（保留中文），附加悬停锚点链接，
+//! 并按标题层级生成嵌套目录供 `article.html.tera` 渲染。
+
+use pulldown_cmark::{html, CowStr, Event, HeadingLevel, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::escape_html;
+
+/// 目录条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TocItem {
+    pub level: u8,
+    pub id: String,
+    pub title: String,
+    pub children: Vec<TocItem>,
+}
+
+/// 由标题文本生成 slug
+///
+/// 保留字母、数字和中日韩文字，空白与连接符统一为 `-`，其余标点丢弃。
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// 同一篇文章内去重：`intro`、`intro-1`、`intro-2`……
+#[derive(Default)]
+struct SlugRegistry {
+    seen: HashMap<String, usize>,
+}
+
+impl SlugRegistry {
+    fn unique(&mut self, base: String) -> String {
+        match self.seen.get(&base).copied() {
+            None => {
+                self.seen.insert(base.clone(), 0);
+                base
+            }
+            Some(mut n) => loop {
+                n += 1;
+                let candidate = format!("{}-{}", base, n);
+                if !self.seen.contains_key(&candidate) {
+                    self.seen.insert(base, n);
+                    self.seen.insert(candidate.clone(), 0);
+                    break candidate;
+                }
+            },
+        }
+    }
+}
+
+fn level_number(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+fn insert_nested(siblings: &mut Vec<TocItem>, item: TocItem) {
+    match siblings.last_mut() {
+        Some(last) if last.level < item.level => insert_nested(&mut last.children, item),
+        _ => siblings.push(item),
+    }
+}
+
+struct PendingHeading<'a> {
+    level: HeadingLevel,
+    id: Option<CowStr<'a>>,
+    classes: Vec<CowStr<'a>>,
+    attrs: Vec<(CowStr<'a>, Option<CowStr<'a>>)>,
+    inner: Vec<Event<'a>>,
+    text: String,
+}
+
+fn render_heading(heading: PendingHeading, id: &str, anchor: bool) -> String {
+    let level = level_number(heading.level);
+    let mut out = format!("<h{} id=\"{}\"", level, escape_html(id));
+    if !heading.classes.is_empty() {
+        let classes: Vec<&str> = heading.classes.iter().map(|c| c.as_ref()).collect();
+        out.push_str(&format!(" class=\"{}\"", escape_html(&classes.join(" "))));
+    }
+    for (name, value) in &heading.attrs {
+        match value {
+            Some(v) => out.push_str(&format!(" {}=\"{}\"", escape_html(name), escape_html(v))),
+            None => out.push_str(&format!(" {}=\"\"", escape_html(name))),
+        }
+    }
+    out.push('>');
+    html::push_html(&mut out, heading.inner.into_iter());
+    if anchor {
+        out.push_str(&format!(
+            "<a class=\"heading-anchor\" href=\"#{}\" aria-hidden=\"true\">#</a>",
+            escape_html(id)
+        ));
+    }
+    out.push_str(&format!("</h{}>\n", level));
+    out
+}
+
+/// 为标题分配 id、插入锚点，并收集目录
+///
+/// 显式写了 `{#id}` 的标题保留原 id；`anchors` 为 false 时只收集目录。
+pub fn build_toc<'a>(events: Vec<Event<'a>>, anchors: bool) -> (Vec<Event<'a>>, Vec<TocItem>) {
+    let mut out = Vec::with_capacity(events.len());
+    let mut toc = Vec::new();
+    let mut slugs = SlugRegistry::default();
+    let mut current: Option<PendingHeading<'a>> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading { level, id, classes, attrs }) => {
+                current = Some(PendingHeading {
+                    level,
+                    id,
+                    classes,
+                    attrs,
+                    inner: Vec::new(),
+                    text: String::new(),
+                });
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(heading) = current.take() {
+                    let title = heading.text.trim().to_string();
+                    let id = match &heading.id {
+                        Some(explicit) => slugs.unique(explicit.to_string()),
+                        None => slugs.unique(slugify(&title)),
+                    };
+                    insert_nested(&mut toc, TocItem {
+                        level: level_number(heading.level),
+                        id: id.clone(),
+                        title,
+                        children: Vec::new(),
+                    });
+                    out.push(Event::Html(CowStr::from(render_heading(heading, &id, anchors))));
+                }
+            }
+            other => match current.as_mut() {
+                Some(heading) => {
+                    if let Event::Text(text) | Event::Code(text) | Event::InlineMath(text) = &other {
+                        heading.text.push_str(text);
+                    }
+                    heading.inner.push(other);
+                }
+                None => out.push(other),
+            },
+        }
+    }
+
+    (out, toc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Options, Parser};
+
+    fn run(md: &str) -> (String, Vec<TocItem>) {
+        let events = Parser::new_ext(md, Options::ENABLE_HEADING_ATTRIBUTES).collect();
+        let (events, toc) = build_toc(events, true);
+        let mut html_out = String::new();
+        html::push_html(&mut html_out, events.into_iter());
+        (html_out, toc)
+    }
+
+    #[test]
+    fn slugify_keeps_cjk() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("Rust 中的 所有权"), "rust-中的-所有权");
+        assert_eq!(slugify("  a -- b__c "), "a-b-c");
+        assert_eq!(slugify("？！"), "section");
+    }
+
+    #[test]
+    fn duplicate_headings_get_suffix() {
+        let (html_out, toc) = run("## 安装\n## 安装\n## 安装-1\n");
+        assert!(html_out.contains(r#"<h2 id="安装">"#));
+        assert!(html_out.contains(r#"<h2 id="安装-1">"#));
+        assert!(html_out.contains(r#"<h2 id="安装-1-1">"#));
+        assert_eq!(toc.len(), 3);
+    }
+
+    #[test]
+    fn explicit_id_and_inline_markup() {
+        let (html_out, toc) = run("# Use `Vec` *now* {#vec .big}\n");
+        assert!(html_out.starts_with(r##"<h1 id="vec" class="big">Use <code>Vec</code> <em>now</em><a class="heading-anchor" href="#vec""##));
+        assert_eq!(toc[0].title, "Use Vec now");
+    }
+
+    #[test]
+    fn toc_is_nested_by_level() {
+        let (_, toc) = run("# A\n## B\n### C\n## D\n# E\n### F\n");
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].children.len(), 2);
+        assert_eq!(toc[0].children[0].children[0].title, "C");
+        assert_eq!(toc[1].children[0].title, "F");
+    }
+}
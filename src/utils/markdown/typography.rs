@@ -0,0 +1,233 @@
+//! 中英文混排排版
+//!
+//! - 中文与英文、数字之间加空格（pangu 风格）
+//! - 中文后的半角 `,;:!?.` 改为全角标点，全角标点两侧的多余空格去掉
+//! - 全角字母、数字改为半角
+//!
+//! 行内代码、代码块、链接、图片、公式和原始 HTML 保持原样；
+//! 代码和公式两侧视作英文，同样会与中文之间加空格。
+
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+
+use crate::utils::cjk::is_cjk;
+
+/// 可以紧跟在中文后面、需要加空格的字符
+fn is_latin_start(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+}
+
+/// 后面紧跟中文时需要加空格的字符
+fn is_latin_end(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '%'
+}
+
+fn is_fullwidth_punct(c: char) -> bool {
+    "，。、；：！？「」『』（）《》【】“”‘’".contains(c)
+}
+
+fn needs_space(prev: char, next: char) -> bool {
+    (is_cjk(prev) && is_latin_start(next)) || (is_latin_end(prev) && is_cjk(next))
+}
+
+/// 全角字母、数字转半角
+fn to_halfwidth(c: char) -> char {
+    match c {
+        '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn to_fullwidth_punct(c: char) -> Option<char> {
+    match c {
+        ',' => Some('，'),
+        ';' => Some('；'),
+        ':' => Some('：'),
+        '!' => Some('！'),
+        '?' => Some('？'),
+        _ => None,
+    }
+}
+
+/// 处理一段文本，`prev` 为之前紧邻的字符
+fn format_text(text: &str, prev: Option<char>) -> String {
+    let chars: Vec<char> = text.chars().map(to_halfwidth).collect();
+
+    // 第一遍：中文后的半角标点改为全角（以前一个非空白字符判断）
+    let mut punct = Vec::with_capacity(chars.len());
+    let mut last_visible = prev.filter(|c| !c.is_whitespace());
+    for (i, &c) in chars.iter().enumerate() {
+        let after_cjk = last_visible.is_some_and(is_cjk);
+        let c = match to_fullwidth_punct(c) {
+            Some(full) if after_cjk => full,
+            None if c == '.' && after_cjk && chars.get(i + 1).is_none_or(|n| n.is_whitespace()) => '。',
+            _ => c,
+        };
+        if !c.is_whitespace() {
+            last_visible = Some(c);
+        }
+        punct.push(c);
+    }
+
+    // 第二遍：去掉全角标点两侧的空格，中英文之间加空格
+    let mut out = String::with_capacity(text.len() + 8);
+    let mut last = prev;
+    for (i, &c) in punct.iter().enumerate() {
+        if c == ' ' {
+            let next_visible = punct[i + 1..].iter().find(|n| **n != ' ');
+            if last.is_some_and(is_fullwidth_punct) || next_visible.is_some_and(|n| is_fullwidth_punct(*n)) {
+                continue;
+            }
+        }
+        if last.is_some_and(|l| needs_space(l, c)) {
+            out.push(' ');
+        }
+        out.push(c);
+        last = Some(c);
+    }
+    out
+}
+
+fn is_inline_tag(tag: &Tag) -> bool {
+    matches!(tag,
+        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+        | Tag::Superscript | Tag::Subscript)
+}
+
+fn is_inline_tag_end(tag: &TagEnd) -> bool {
+    matches!(tag,
+        TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image
+        | TagEnd::Superscript | TagEnd::Subscript)
+}
+
+/// 在已输出事件末尾插入空格，位置在刚打开的行内标签之前
+fn push_space_before_open_tags(out: &mut Vec<Event<'_>>) {
+    let mut pos = out.len();
+    while pos > 0 && matches!(&out[pos - 1], Event::Start(tag) if is_inline_tag(tag)) {
+        pos -= 1;
+    }
+    out.insert(pos, Event::Text(CowStr::Borrowed(" ")));
+}
+
+/// 对事件流中的正文文本做中英文混排处理
+pub fn format_mixed_text(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut out = Vec::with_capacity(events.len());
+    // 同一段落内紧邻的上一个字符，跨越段落等块级边界时清空
+    let mut prev: Option<char> = None;
+    // 链接、图片内的文字不改动
+    let mut opaque_depth = 0usize;
+    let mut in_code_block = false;
+
+    for event in events {
+        match event {
+            Event::Text(text) if in_code_block => out.push(Event::Text(text)),
+            Event::Text(text) => {
+                let first = text.chars().next().map(to_halfwidth);
+                if opaque_depth > 0 {
+                    if let (Some(p), Some(f)) = (prev, first) {
+                        if needs_space(p, f) && out.last().is_some_and(|e| matches!(e, Event::Start(_))) {
+                            push_space_before_open_tags(&mut out);
+                        }
+                    }
+                    prev = text.chars().last().or(prev);
+                    out.push(Event::Text(text));
+                    continue;
+                }
+
+                // 刚打开行内标签时，空格移到标签之前
+                let mut formatted = format_text(&text, prev);
+                let just_opened = out.last().is_some_and(|e| matches!(e, Event::Start(tag) if is_inline_tag(tag)));
+                if just_opened && formatted.starts_with(' ') && !text.starts_with(' ') {
+                    formatted.remove(0);
+                    push_space_before_open_tags(&mut out);
+                }
+                prev = formatted.chars().last().or(prev);
+                out.push(Event::Text(CowStr::from(formatted)));
+            }
+            Event::Code(_) | Event::InlineMath(_) => {
+                // 代码和公式视作英文
+                if opaque_depth == 0 && prev.is_some_and(|p| needs_space(p, 'a')) {
+                    push_space_before_open_tags(&mut out);
+                }
+                prev = Some('a');
+                out.push(event);
+            }
+            Event::Start(tag) => {
+                if is_inline_tag(&tag) {
+                    if matches!(tag, Tag::Link { .. } | Tag::Image { .. }) {
+                        opaque_depth += 1;
+                    }
+                } else {
+                    prev = None;
+                    if matches!(tag, Tag::CodeBlock(_)) {
+                        in_code_block = true;
+                    }
+                }
+                out.push(Event::Start(tag));
+            }
+            Event::End(tag) => {
+                if is_inline_tag_end(&tag) {
+                    if matches!(tag, TagEnd::Link | TagEnd::Image) {
+                        opaque_depth = opaque_depth.saturating_sub(1);
+                    }
+                } else {
+                    prev = None;
+                    if matches!(tag, TagEnd::CodeBlock) {
+                        in_code_block = false;
+                    }
+                }
+                out.push(Event::End(tag));
+            }
+            other => {
+                // 换行、原始 HTML、短代码占位符等：不跨越它们加空格
+                prev = None;
+                out.push(other);
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{html, Options, Parser};
+
+    fn run(md: &str) -> String {
+        let events = Parser::new_ext(md, Options::ENABLE_MATH).collect();
+        let mut out = String::new();
+        html::push_html(&mut out, format_mixed_text(events).into_iter());
+        out
+    }
+
+    #[test]
+    fn spaces_between_cjk_and_latin() {
+        assert_eq!(format_text("在Rust中使用2个线程", None), "在 Rust 中使用 2 个线程");
+        assert_eq!(format_text("提升了50%的性能", None), "提升了 50% 的性能");
+        assert_eq!(format_text("已经有 空格 的 English 不变", None), "已经有 空格 的 English 不变");
+    }
+
+    #[test]
+    fn normalizes_punctuation() {
+        assert_eq!(format_text("你好,世界!真的吗?", None), "你好，世界！真的吗？");
+        assert_eq!(format_text("结束了.", None), "结束了。");
+        assert_eq!(format_text("你好 ， 世界 。", None), "你好，世界。");
+        assert_eq!(format_text("版本1.0, ok: yes", None), "版本 1.0, ok: yes");
+        assert_eq!(format_text("ＲＵＳＴ１２３", None), "RUST123");
+    }
+
+    #[test]
+    fn spaces_around_inline_markup() {
+        assert_eq!(run("使用`Vec`类型"), "<p>使用 <code>Vec</code> 类型</p>\n");
+        assert_eq!(run("学习**Rust**语言"), "<p>学习 <strong>Rust</strong> 语言</p>\n");
+        assert_eq!(run("公式$x^2$很简单"), "<p>公式 <span class=\"math math-inline\">x^2</span> 很简单</p>\n");
+    }
+
+    #[test]
+    fn leaves_code_links_and_math_alone() {
+        assert_eq!(run("见[Rust文档,第1章](https://doc.rust-lang.org)"), "<p>见 <a href=\"https://doc.rust-lang.org\">Rust文档,第1章</a></p>\n");
+        assert!(run("```\n中文abc,好\n```\n").contains("中文abc,好"));
+        assert!(run("`中文abc`").contains("<code>中文abc</code>"));
+        assert!(run("$$中文abc$$").contains("中文abc"));
+    }
+}
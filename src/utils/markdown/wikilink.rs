@@ -0,0 +1,159 @@
+//! 站内维基链接
+//!
+//! - `[[文章标题]]`：按标题（忽略大小写）链接到文章
+//! - `[[#42]]`：按 id 链接到文章，链接文字为文章标题
+//! - `[[目标|显示文字]]`：自定义链接文字
+//!
+//! 找不到目标的链接渲染为 `wikilink-broken`，并记录在渲染结果中供后台提示。
+
+use pulldown_cmark::{CowStr, Event, LinkType, Options, Parser, Tag, TagEnd};
+
+use super::{escape_html, ArticleRef, RenderContext};
+
+/// 维基链接的目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WikiTarget {
+    Id(i32),
+    Title(String),
+}
+
+impl WikiTarget {
+    fn parse(dest: &str) -> WikiTarget {
+        let dest = dest.trim();
+        match dest.strip_prefix('#').and_then(|id| id.parse().ok()) {
+            Some(id) => WikiTarget::Id(id),
+            None => WikiTarget::Title(dest.to_string()),
+        }
+    }
+
+    fn resolve<'c>(&self, ctx: &'c RenderContext) -> Option<&'c ArticleRef> {
+        match self {
+            WikiTarget::Id(id) => ctx.articles.get(id),
+            WikiTarget::Title(title) => ctx.article_by_title(title),
+        }
+    }
+}
+
+/// 维基链接处理结果
+#[derive(Debug, Default)]
+pub struct WikiLinks {
+    /// 成功解析的文章 id
+    pub resolved: Vec<i32>,
+    /// 无法解析的原始目标，如 `不存在的文章`、`#99`
+    pub broken: Vec<String>,
+}
+
+/// 收集 Markdown 中所有维基链接的目标（代码中的不算）
+pub fn referenced_wikilinks(md: &str) -> Vec<WikiTarget> {
+    Parser::new_ext(md, Options::ENABLE_WIKILINKS)
+        .filter_map(|event| match event {
+            Event::Start(Tag::Link { link_type: LinkType::WikiLink { .. }, dest_url, .. }) => {
+                Some(WikiTarget::parse(&dest_url))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 将维基链接替换为站内文章链接
+pub fn resolve_wikilinks<'a>(
+    events: Vec<Event<'a>>,
+    ctx: &RenderContext,
+    links: &mut WikiLinks,
+) -> Vec<Event<'a>> {
+    let mut out = Vec::with_capacity(events.len());
+    // 当前维基链接的闭合标签
+    let mut closing: Option<&'static str> = None;
+    // 是否已用文章标题替换了链接文字
+    let mut replacing_text = false;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Link { link_type: LinkType::WikiLink { has_pothole }, dest_url, .. }) => {
+                let target = WikiTarget::parse(&dest_url);
+                match target.resolve(ctx) {
+                    Some(article) => {
+                        links.resolved.push(article.id);
+                        closing = Some("</a>");
+                        out.push(Event::InlineHtml(CowStr::from(format!(
+                            "<a class=\"wikilink\" href=\"{}\">",
+                            escape_html(&article.url)
+                        ))));
+                        if !has_pothole && matches!(target, WikiTarget::Id(_)) {
+                            out.push(Event::Text(CowStr::from(article.title.clone())));
+                            replacing_text = true;
+                        }
+                    }
+                    None => {
+                        let raw = dest_url.trim().to_string();
+                        if !links.broken.contains(&raw) {
+                            links.broken.push(raw);
+                        }
+                        closing = Some("</span>");
+                        out.push(Event::InlineHtml(CowStr::from(format!(
+                            "<span class=\"wikilink wikilink-broken\" title=\"{}\">",
+                            escape_html(&format!("文章不存在：{}", dest_url.trim()))
+                        ))));
+                    }
+                }
+            }
+            Event::End(TagEnd::Link) if closing.is_some() => {
+                out.push(Event::InlineHtml(CowStr::Borrowed(closing.take().unwrap_or_default())));
+                replacing_text = false;
+            }
+            _ if replacing_text => {}
+            other => out.push(other),
+        }
+    }
+
+    links.resolved.sort_unstable();
+    links.resolved.dedup();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::html;
+
+    fn ctx() -> RenderContext {
+        let mut ctx = RenderContext::default();
+        for (id, title, slug) in [(1, "Rust 入门", "rust-ru-men"), (42, "所有权", "suo-you-quan")] {
+            ctx.articles.insert(id, ArticleRef { id, title: title.to_string(), url: format!("/posts/2025/{}", slug) });
+        }
+        ctx
+    }
+
+    fn run(md: &str) -> (String, WikiLinks) {
+        let events = Parser::new_ext(md, Options::ENABLE_WIKILINKS).collect();
+        let mut links = WikiLinks::default();
+        let events = resolve_wikilinks(events, &ctx(), &mut links);
+        let mut out = String::new();
+        html::push_html(&mut out, events.into_iter());
+        (out, links)
+    }
+
+    #[test]
+    fn resolves_by_title_and_id() {
+        let (out, links) = run("见 [[rust 入门]] 和 [[#42]]，以及 [[#42|这篇]]");
+        assert!(out.contains(r#"<a class="wikilink" href="/posts/2025/rust-ru-men">rust 入门</a>"#));
+        assert!(out.contains(r#"<a class="wikilink" href="/posts/2025/suo-you-quan">所有权</a>"#));
+        assert!(out.contains(r#"<a class="wikilink" href="/posts/2025/suo-you-quan">这篇</a>"#));
+        assert_eq!(links.resolved, vec![1, 42]);
+        assert!(links.broken.is_empty());
+    }
+
+    #[test]
+    fn broken_links_are_flagged() {
+        let (out, links) = run("[[不存在]] 与 [[#7|七]]");
+        assert!(out.contains(r#"<span class="wikilink wikilink-broken" title="文章不存在：不存在">不存在</span>"#));
+        assert!(out.contains(">七</span>"));
+        assert_eq!(links.broken, vec!["不存在", "#7"]);
+    }
+
+    #[test]
+    fn collects_targets_outside_code() {
+        let targets = referenced_wikilinks("[[A]] `[[B]]`\n\n```\n[[C]]\n```\n[[#3|x]]");
+        assert_eq!(targets, vec![WikiTarget::Title("A".into()), WikiTarget::Id(3)]);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod cjk;
+pub mod diff;
+pub mod markdown;
+pub mod similarity;
+pub mod slug;
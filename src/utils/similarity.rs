@@ -0,0 +1,72 @@
+//! 标题相似度
+//!
+//! 用于相关文章排序。中文每个字、英文每个单词为一个词元，
+//! 按相邻词元组成的二元组计算 Dice 系数：`Rust 所有权入门` 与 `Rust 生命周期入门` 共有“入门”。
+
+use std::collections::HashSet;
+
+use crate::utils::cjk::is_cjk;
+
+/// 拆分词元：中文按字，英文和数字按词（小写），其余字符作为分隔
+fn tokens(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                out.push(std::mem::take(&mut word));
+            }
+            out.push(c.to_string());
+        } else if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            out.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        out.push(word);
+    }
+    out
+}
+
+/// 相邻词元二元组；只有一个词元时取其本身
+fn bigrams(text: &str) -> HashSet<String> {
+    let tokens = tokens(text);
+    if tokens.len() == 1 {
+        return tokens.into_iter().collect();
+    }
+    tokens.windows(2).map(|pair| format!("{}\u{0}{}", pair[0], pair[1])).collect()
+}
+
+/// 两个标题的相似度，范围 0 到 1
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count();
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_cjk_characters_and_latin_words() {
+        assert_eq!(tokens("Rust 所有权入门"), ["rust", "所", "有", "权", "入", "门"]);
+        assert_eq!(tokens("学习Tokio-1.0"), ["学", "习", "tokio", "1", "0"]);
+    }
+
+    #[test]
+    fn scores_shared_bigrams() {
+        assert_eq!(title_similarity("Rust 所有权", "rust 所有权"), 1.0);
+        assert_eq!(title_similarity("旅行日记", "Rust 入门"), 0.0);
+        assert_eq!(title_similarity("", "Rust"), 0.0);
+        assert_eq!(title_similarity("Rust", "Rust"), 1.0);
+
+        let close = title_similarity("Rust 所有权入门", "Rust 生命周期入门");
+        let far = title_similarity("Rust 所有权入门", "Python 入坑");
+        assert!(close > far && far == 0.0, "{} {}", close, far);
+    }
+}
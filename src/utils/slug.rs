@@ -0,0 +1,52 @@
+//! 文章 slug
+//!
+//! 由标题生成 URL 中使用的 slug，中文按拼音转写：`Rust 所有权入门` → `rust-suo-you-quan-ru-men`。
+
+use deunicode::deunicode;
+
+/// slug 最大长度（字节）
+const MAX_LEN: usize = 80;
+
+/// 生成 slug：转写为 ASCII，只保留小写字母和数字，其余字符合并为 `-`
+///
+/// 也用于规范化后台手动填写的 slug；结果为空时返回 `post`。
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in deunicode(text).chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    if slug.len() > MAX_LEN {
+        slug.truncate(MAX_LEN);
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "post".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transliterates_chinese() {
+        assert_eq!(slugify("Rust 所有权入门"), "rust-suo-you-quan-ru-men");
+        assert_eq!(slugify("你好，世界！"), "ni-hao-shi-jie");
+    }
+
+    #[test]
+    fn normalizes_input() {
+        assert_eq!(slugify("  Hello, World!  "), "hello-world");
+        assert_eq!(slugify("my--custom__slug"), "my-custom-slug");
+        assert_eq!(slugify("Café déjà vu"), "cafe-deja-vu");
+        assert_eq!(slugify("？！"), "post");
+        assert!(slugify(&"长".repeat(100)).len() <= MAX_LEN);
+    }
+}